@@ -1,7 +1,11 @@
 //! A collection of lines.
+use crate::line::action::Action;
 use crate::line::Line;
+use crate::parse::{self, Item, ParseError};
 use itertools::Itertools;
 use std::fmt;
+use std::mem;
+use std::str::FromStr;
 /// A collection of lines.
 ///
 /// # Example
@@ -18,27 +22,101 @@ use std::fmt;
 ///         Line::Condition(Condition::Identified(true)),
 ///         Line::Action(Action::SetBorderColor(RGBA{r: 255, g: 0, b: 0, a: 255})),
 ///         ],
-///         is_hidden: false
+///         is_hidden: false,
+///         comment: None,
 ///         };
 /// # assert_eq!(block.to_string(), "Show\n\tAreaLevel > 1\n\tIdentified true\n\tSetBorderColor 255 0 0 255");
+/// # let filter = block.to_string().parse::<libfilter::filter::Filter>().unwrap();
+/// # assert_eq!(filter.sections[0].blocks[0], block);
 /// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Block {
     /// The lines in the block.
     pub lines: Vec<Line>,
 
     /// Whether to hide the items or not.
     pub is_hidden: bool,
+
+    /// The comment after `Show` or `Hide`, often holding tags such as `$type->currency $tier->t1`.
+    pub comment: Option<String>,
+}
+
+impl Block {
+    /// The whitespace separated words of the comment, such as `$tier->t1`.
+    ///
+    /// # Example
+    /// ```
+    /// # use libfilter::block::Block;
+    /// let block = Block { lines: vec![], is_hidden: false, comment: Some(String::from("$type->currency $tier->t1")) };
+    /// # assert_eq!(block.tags().collect::<Vec<_>>(), vec!["$type->currency", "$tier->t1"]);
+    /// # assert_eq!(block.to_string(), "Show # $type->currency $tier->t1\n\t");
+    /// ```
+    pub fn tags(&self) -> impl Iterator<Item = &str> {
+        self.comment
+            .iter()
+            .flat_map(|comment| comment.split_whitespace())
+    }
+
+    /// Sets an action, replacing any action of the same kind.
+    ///
+    /// # Example
+    /// ```
+    /// # use libfilter::block::Block;
+    /// # use libfilter::line::Line;
+    /// # use libfilter::line::action::Action;
+    /// let mut block = Block { lines: vec![Line::Action(Action::SetFontSize(30))], is_hidden: false, comment: None };
+    /// block.set_action(Action::SetFontSize(45));
+    /// block.set_action(Action::EnableDropSound);
+    /// # assert_eq!(block.to_string(), "Show\n\tSetFontSize 45\n\tEnableDropSound");
+    /// ```
+    pub fn set_action(&mut self, action: Action) {
+        let existing = self.lines.iter_mut().find(|line| match line {
+            Line::Action(current) => mem::discriminant(current) == mem::discriminant(&action),
+            _ => false,
+        });
+        match existing {
+            Some(line) => *line = Line::Action(action),
+            None => self.lines.push(Line::Action(action)),
+        }
+    }
 }
 
 impl fmt::Display for Block {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut block_string = String::new();
         if self.is_hidden {
-            block_string.push_str("Hide\n\t");
+            block_string.push_str("Hide");
         } else {
-            block_string.push_str("Show\n\t");
+            block_string.push_str("Show");
         }
+        if let Some(comment) = &self.comment {
+            block_string.push_str(" # ");
+            block_string.push_str(comment);
+        }
+        block_string.push_str("\n\t");
         block_string.push_str(&self.lines.iter().join("\n\t"));
         write!(f, "{}", block_string)
     }
 }
+
+/// Parses a single `Show` or `Hide` block.
+///
+/// # Example
+/// ```
+/// # use libfilter::block::Block;
+/// let block = "Hide # $type->chaff\n\tClass \"Rings\"".parse::<Block>().unwrap();
+/// # assert!(block.is_hidden);
+/// # assert_eq!(block.to_string(), "Hide # $type->chaff\n\tClass \"Rings\"");
+/// # assert!("Show\nShow".parse::<Block>().is_err());
+/// ```
+impl FromStr for Block {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut items = parse::items(s)?.into_iter();
+        match (items.next(), items.next()) {
+            (Some(Item::Block(block)), None) => Ok(block),
+            _ => Err(ParseError::new("expected a single block")),
+        }
+    }
+}
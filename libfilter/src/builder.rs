@@ -0,0 +1,467 @@
+//! Fluent construction of blocks and sections.
+//!
+//! A block is started with [Block::show] or [Block::hide] and a section with [Section::new],
+//! then lines and blocks are added one call at a time. Nothing is checked: an empty block or a
+//! section with an empty header is written as it is.
+use crate::block::Block;
+use crate::line::action::Action;
+use crate::line::color::Color;
+use crate::line::condition::Condition;
+use crate::line::gem_quality::GemQuality;
+use crate::line::import::Import;
+use crate::line::influence::Influence;
+use crate::line::operator::Operator;
+use crate::line::rarity::Rarity;
+use crate::line::rgba::RGBA;
+use crate::line::shape::Shape;
+use crate::line::size::Size;
+use crate::line::Line;
+use crate::section::Section;
+
+impl Block {
+    /// Starts a block that shows the items it matches.
+    ///
+    /// # Example
+    /// ```
+    /// # use libfilter::block::Block;
+    /// # use libfilter::{Color, RGBA};
+    /// let block = Block::show()
+    ///     .class(["Rings"])
+    ///     .item_level_ge(75)
+    ///     .text_color(RGBA { r: 255, g: 0, b: 0, a: 255 })
+    ///     .beam(Color::Red);
+    /// # assert_eq!(block.to_string(), "Show\n\tClass \"Rings\"\n\tItemLevel >= 75\n\tSetTextColor 255 0 0 255\n\tPlayEffect Red");
+    /// ```
+    pub fn show() -> Block {
+        Block {
+            lines: vec![],
+            is_hidden: false,
+            comment: None,
+        }
+    }
+
+    /// Starts a block that hides the items it matches.
+    ///
+    /// # Example
+    /// ```
+    /// # use libfilter::block::Block;
+    /// let block = Block::hide().base_type(["Iron Ring", "Coral Ring"]).identified(false);
+    /// # assert_eq!(block.to_string(), "Hide\n\tBaseType \"Iron Ring\" \"Coral Ring\"\n\tIdentified false");
+    /// ```
+    pub fn hide() -> Block {
+        Block {
+            is_hidden: true,
+            ..Block::show()
+        }
+    }
+
+    /// Sets the comment written after `Show` or `Hide`.
+    pub fn comment(mut self, comment: impl Into<String>) -> Block {
+        self.comment = Some(comment.into());
+        self
+    }
+
+    /// Adds a condition.
+    pub fn condition(mut self, condition: Condition) -> Block {
+        self.lines.push(Line::Condition(condition));
+        self
+    }
+
+    /// Sets an action, replacing any action of the same kind.
+    pub fn action(mut self, action: Action) -> Block {
+        self.set_action(action);
+        self
+    }
+
+    /// Adds an `AreaLevel` condition.
+    pub fn area_level(self, operator: Operator, value: u8) -> Block {
+        self.condition(Condition::AreaLevel((operator, value)))
+    }
+
+    /// Adds an `AreaLevel >= value` condition.
+    pub fn area_level_ge(self, value: u8) -> Block {
+        self.area_level(Operator::GreaterThanOrEqual, value)
+    }
+
+    /// Adds an `ItemLevel` condition.
+    pub fn item_level(self, operator: Operator, value: u8) -> Block {
+        self.condition(Condition::ItemLevel((operator, value)))
+    }
+
+    /// Adds an `ItemLevel >= value` condition.
+    pub fn item_level_ge(self, value: u8) -> Block {
+        self.item_level(Operator::GreaterThanOrEqual, value)
+    }
+
+    /// Adds a `DropLevel` condition.
+    pub fn drop_level(self, operator: Operator, value: u8) -> Block {
+        self.condition(Condition::DropLevel((operator, value)))
+    }
+
+    /// Adds a `DropLevel >= value` condition.
+    pub fn drop_level_ge(self, value: u8) -> Block {
+        self.drop_level(Operator::GreaterThanOrEqual, value)
+    }
+
+    /// Adds a `Quality` condition.
+    pub fn quality(self, operator: Operator, value: u8) -> Block {
+        self.condition(Condition::Quality((operator, value)))
+    }
+
+    /// Adds a `Quality >= value` condition.
+    pub fn quality_ge(self, value: u8) -> Block {
+        self.quality(Operator::GreaterThanOrEqual, value)
+    }
+
+    /// Adds a `Rarity` condition.
+    pub fn rarity(self, operator: Operator, rarity: Rarity) -> Block {
+        self.condition(Condition::Rarity((operator, vec![rarity])))
+    }
+
+    /// Adds a `Class` condition matching any of the values.
+    pub fn class<I, S>(self, values: I) -> Block
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.condition(Condition::Class((
+            None,
+            values.into_iter().map(Into::into).collect(),
+        )))
+    }
+
+    /// Adds a `BaseType` condition matching any of the values.
+    pub fn base_type<I, S>(self, values: I) -> Block
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.condition(Condition::BaseType((
+            None,
+            values.into_iter().map(Into::into).collect(),
+        )))
+    }
+
+    /// Adds a `BaseType ==` condition matching any of the values as whole names only.
+    ///
+    /// # Example
+    /// ```
+    /// # use libfilter::block::Block;
+    /// let block = Block::show().base_type_eq(["Exalted Orb"]);
+    /// # assert_eq!(block.to_string(), "Show\n\tBaseType == \"Exalted Orb\"");
+    /// ```
+    pub fn base_type_eq<I, S>(self, values: I) -> Block
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.condition(Condition::BaseType((
+            Some(Operator::Equal),
+            values.into_iter().map(Into::into).collect(),
+        )))
+    }
+
+    /// Adds a `Prophecy` condition.
+    pub fn prophecy(self, value: impl Into<String>) -> Block {
+        self.condition(Condition::Prophecy(value.into()))
+    }
+
+    /// Adds a `LinkedSockets` condition.
+    pub fn linked_sockets(self, operator: Operator, value: u8) -> Block {
+        self.condition(Condition::LinkedSockets((operator, value)))
+    }
+
+    /// Adds a `LinkedSockets >= value` condition.
+    pub fn linked_sockets_ge(self, value: u8) -> Block {
+        self.linked_sockets(Operator::GreaterThanOrEqual, value)
+    }
+
+    /// Adds a `SocketGroup` condition, such as `SocketGroup >= 5GGG` for a count of 5 and colors `GGG`.
+    pub fn socket_group(self, operator: Operator, count: u8, colors: impl Into<String>) -> Block {
+        self.condition(Condition::SocketGroup((
+            Some(operator),
+            colors.into(),
+            count,
+        )))
+    }
+
+    /// Adds a `Sockets` condition, such as `Sockets >= 5GGG` for a count of 5 and colors `GGG`.
+    pub fn sockets(self, operator: Operator, count: u8, colors: impl Into<String>) -> Block {
+        self.condition(Condition::Sockets((Some(operator), colors.into(), count)))
+    }
+
+    /// Adds a `Height` condition.
+    pub fn height(self, operator: Operator, value: u8) -> Block {
+        self.condition(Condition::Height((operator, value)))
+    }
+
+    /// Adds a `Height >= value` condition.
+    pub fn height_ge(self, value: u8) -> Block {
+        self.height(Operator::GreaterThanOrEqual, value)
+    }
+
+    /// Adds a `Width` condition.
+    pub fn width(self, operator: Operator, value: u8) -> Block {
+        self.condition(Condition::Width((operator, value)))
+    }
+
+    /// Adds a `Width >= value` condition.
+    pub fn width_ge(self, value: u8) -> Block {
+        self.width(Operator::GreaterThanOrEqual, value)
+    }
+
+    /// Adds a `HasExplicitMod` condition.
+    pub fn has_explicit_mod(self, value: impl Into<String>) -> Block {
+        self.condition(Condition::HasExplicitMod((None, None, vec![value.into()])))
+    }
+
+    /// Adds an `AnyEnchantment` condition.
+    pub fn any_enchantment(self, value: bool) -> Block {
+        self.condition(Condition::AnyEnchantment(value))
+    }
+
+    /// Adds a `HasEnchantment` condition.
+    pub fn has_enchantment(self, value: impl Into<String>) -> Block {
+        self.condition(Condition::HasEnchantment(value.into()))
+    }
+
+    /// Adds an `EnchantmentPassiveNode` condition.
+    pub fn enchantment_passive_node(self, value: impl Into<String>) -> Block {
+        self.condition(Condition::EnchantmentPassiveNode((
+            None,
+            vec![value.into()],
+        )))
+    }
+
+    /// Adds an `EnchantmentPassiveNum` condition.
+    pub fn enchantment_passive_num(self, operator: Operator, value: u8) -> Block {
+        self.condition(Condition::EnchantmentPassiveNum((operator, value)))
+    }
+
+    /// Adds an `EnchantmentPassiveNum >= value` condition.
+    pub fn enchantment_passive_num_ge(self, value: u8) -> Block {
+        self.enchantment_passive_num(Operator::GreaterThanOrEqual, value)
+    }
+
+    /// Adds a `StackSize` condition.
+    pub fn stack_size(self, operator: Operator, value: u8) -> Block {
+        self.condition(Condition::StackSize((operator, value)))
+    }
+
+    /// Adds a `StackSize >= value` condition.
+    pub fn stack_size_ge(self, value: u8) -> Block {
+        self.stack_size(Operator::GreaterThanOrEqual, value)
+    }
+
+    /// Adds a `GemLevel` condition.
+    pub fn gem_level(self, operator: Operator, value: u8) -> Block {
+        self.condition(Condition::GemLevel((operator, value)))
+    }
+
+    /// Adds a `GemLevel >= value` condition.
+    pub fn gem_level_ge(self, value: u8) -> Block {
+        self.gem_level(Operator::GreaterThanOrEqual, value)
+    }
+
+    /// Adds a `GemQualityType` condition.
+    pub fn gem_quality_type(self, value: GemQuality) -> Block {
+        self.condition(Condition::GemQualityType(value))
+    }
+
+    /// Adds an `AlternativeQuality` condition.
+    pub fn alternative_quality(self, value: bool) -> Block {
+        self.condition(Condition::AlternativeQuality(value))
+    }
+
+    /// Adds a `Replica` condition.
+    pub fn replica(self, value: bool) -> Block {
+        self.condition(Condition::Replica(value))
+    }
+
+    /// Adds an `Identified` condition.
+    pub fn identified(self, value: bool) -> Block {
+        self.condition(Condition::Identified(value))
+    }
+
+    /// Adds a `Corrupted` condition.
+    pub fn corrupted(self, value: bool) -> Block {
+        self.condition(Condition::Corrupted(value))
+    }
+
+    /// Adds a `CorruptedMods` condition.
+    pub fn corrupted_mods(self, operator: Operator, value: u8) -> Block {
+        self.condition(Condition::CorruptedMods((operator, value)))
+    }
+
+    /// Adds a `CorruptedMods >= value` condition.
+    pub fn corrupted_mods_ge(self, value: u8) -> Block {
+        self.corrupted_mods(Operator::GreaterThanOrEqual, value)
+    }
+
+    /// Adds a `Mirrored` condition.
+    pub fn mirrored(self, value: bool) -> Block {
+        self.condition(Condition::Mirrored(value))
+    }
+
+    /// Adds an `ElderItem` condition.
+    pub fn elder_item(self, value: bool) -> Block {
+        self.condition(Condition::ElderItem(value))
+    }
+
+    /// Adds a `ShaperItem` condition.
+    pub fn shaper_item(self, value: bool) -> Block {
+        self.condition(Condition::ShaperItem(value))
+    }
+
+    /// Adds a `HasInfluence` condition.
+    pub fn has_influence(self, value: Influence) -> Block {
+        self.condition(Condition::HasInfluence((None, vec![value])))
+    }
+
+    /// Adds a `FracturedItem` condition.
+    pub fn fractured_item(self, value: bool) -> Block {
+        self.condition(Condition::FracturedItem(value))
+    }
+
+    /// Adds a `SynthesisedItem` condition.
+    pub fn synthesised_item(self, value: bool) -> Block {
+        self.condition(Condition::SynthesisedItem(value))
+    }
+
+    /// Adds an `ElderMap` condition.
+    pub fn elder_map(self, value: bool) -> Block {
+        self.condition(Condition::ElderMap(value))
+    }
+
+    /// Adds a `ShapedMap` condition.
+    pub fn shaped_map(self, value: bool) -> Block {
+        self.condition(Condition::ShapedMap(value))
+    }
+
+    /// Adds a `BlightedMap` condition.
+    pub fn blighted_map(self, value: bool) -> Block {
+        self.condition(Condition::BlightedMap(value))
+    }
+
+    /// Adds an `UberBlightedMap` condition.
+    pub fn uber_blighted_map(self, value: bool) -> Block {
+        self.condition(Condition::UberBlightedMap(value))
+    }
+
+    /// Adds a `MapTier` condition.
+    pub fn map_tier(self, operator: Operator, value: u8) -> Block {
+        self.condition(Condition::MapTier((operator, value)))
+    }
+
+    /// Adds a `MapTier >= value` condition.
+    pub fn map_tier_ge(self, value: u8) -> Block {
+        self.map_tier(Operator::GreaterThanOrEqual, value)
+    }
+
+    /// Sets the border color.
+    pub fn border_color(self, color: RGBA) -> Block {
+        self.action(Action::SetBorderColor(color))
+    }
+
+    /// Sets the text color.
+    pub fn text_color(self, color: RGBA) -> Block {
+        self.action(Action::SetTextColor(color))
+    }
+
+    /// Sets the background color.
+    pub fn background_color(self, color: RGBA) -> Block {
+        self.action(Action::SetBackgroundColor(color))
+    }
+
+    /// Sets the font size.
+    pub fn font_size(self, size: u16) -> Block {
+        self.action(Action::SetFontSize(size))
+    }
+
+    /// Plays an alert sound with the given id and volume.
+    pub fn alert_sound(self, id: u8, volume: u16) -> Block {
+        self.action(Action::PlayAlertSound((id, volume)))
+    }
+
+    /// Plays an alert sound with volume relative to where the item dropped.
+    pub fn alert_sound_positional(self, id: u8, volume: u16) -> Block {
+        self.action(Action::PlayAlertSoundPositional((id, volume)))
+    }
+
+    /// Plays a custom alert sound from a file.
+    pub fn custom_alert_sound(self, path: impl Into<String>) -> Block {
+        self.action(Action::CustomAlertSound((path.into(), None)))
+    }
+
+    /// Disables the drop sound.
+    pub fn disable_drop_sound(self) -> Block {
+        self.action(Action::DisableDroupSound)
+    }
+
+    /// Enables the drop sound.
+    pub fn enable_drop_sound(self) -> Block {
+        self.action(Action::EnableDropSound)
+    }
+
+    /// Sets the minimap icon.
+    pub fn minimap_icon(self, size: Size, color: Color, shape: Shape) -> Block {
+        self.action(Action::MinimapIcon((size, color, shape)))
+    }
+
+    /// Plays a permanent light beam.
+    pub fn beam(self, color: Color) -> Block {
+        self.action(Action::PlayEffect((color, false)))
+    }
+
+    /// Plays a light beam that only shows when the item drops.
+    pub fn temporary_beam(self, color: Color) -> Block {
+        self.action(Action::PlayEffect((color, true)))
+    }
+}
+
+impl Section {
+    /// Starts a section with a header, which can be used to jump around in the filter.
+    ///
+    /// # Example
+    /// ```
+    /// # use libfilter::section::Section;
+    /// # use libfilter::block::Block;
+    /// let section = Section::new("RING")
+    ///     .description("Rings")
+    ///     .block(Block::show().class(["Rings"]))
+    ///     .block(Block::hide());
+    /// # assert_eq!(section.to_string(), "###RING\n###Rings\nShow\n\tClass \"Rings\"\nHide\n\t");
+    /// ```
+    pub fn new(header: impl Into<String>) -> Section {
+        Section {
+            header: header.into(),
+            blocks: vec![],
+            description: String::new(),
+            imports: vec![],
+        }
+    }
+
+    /// Sets the description.
+    pub fn description(mut self, description: impl Into<String>) -> Section {
+        self.description = description.into();
+        self
+    }
+
+    /// Adds a block.
+    pub fn block(mut self, block: Block) -> Section {
+        self.blocks.push(block);
+        self
+    }
+
+    /// Adds several blocks.
+    pub fn blocks(mut self, blocks: impl IntoIterator<Item = Block>) -> Section {
+        self.blocks.extend(blocks);
+        self
+    }
+
+    /// Imports another filter after the blocks.
+    pub fn import(mut self, import: Import) -> Section {
+        self.imports.push(import);
+        self
+    }
+}
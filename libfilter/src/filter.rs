@@ -1,11 +1,18 @@
 //! Customizes what players see when an item drops in the game.
+use crate::block::Block;
+use crate::line::import::Import;
+use crate::parse::{self, Item, ParseError};
 use crate::section::Section;
-use std::fs::File;
+use std::fmt;
+use std::fs::{self, File};
 use std::io::{Error, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 /// Customizes what players see when an item drops in the game.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct Filter {
-    /// The sections of the filter.
+    /// The sections of the filter, each with the imports that follow its blocks.
     pub sections: Vec<Section>,
 }
 
@@ -43,17 +50,290 @@ impl Filter {
     ///                 Line::Condition(Condition::Identified(true)),
     ///                 Line::Action(Action::SetBorderColor(RGBA{r: 255, g: 0, b: 0, a: 255})),
     ///                 ],
-    ///             is_hidden: false
+    ///             is_hidden: false,
+    ///             comment: None,
     ///         },
     ///     ],
+    ///     imports: vec![],
     /// });
     /// filter.write_to_file("filter");
     /// ```
     pub fn write_to_file(&self, path: &str) -> Result<(), Error> {
         let mut file = File::create(path)?;
+        write!(file, "{}", self)
+    }
+
+    /// Writes the filter as a base file, and `overrides` as a file that imports it.
+    ///
+    /// The blocks in the override file come before the imported base, so they take precedence.
+    ///
+    /// # Example
+    /// ```
+    /// # use libfilter::filter::Filter;
+    /// # use libfilter::section::Section;
+    /// # use libfilter::block::Block;
+    /// # use libfilter::line::condition::Condition;
+    /// # use libfilter::line::Line;
+    /// let dir = std::env::temp_dir().join("libfilter_write_with_overrides");
+    /// std::fs::create_dir_all(&dir).unwrap();
+    /// let base_path = dir.join("base.filter");
+    /// let override_path = dir.join("personal.filter");
+    ///
+    /// let base = "###BASE\n###The base filter\nShow\n\tIdentified true".parse::<Filter>().unwrap();
+    /// let overrides = "###MINE\n###Personal tweaks\nHide\n\tCorrupted true".parse::<Filter>().unwrap();
+    /// base.write_with_overrides(&overrides, base_path.to_str().unwrap(), override_path.to_str().unwrap()).unwrap();
+    ///
+    /// # assert_eq!(std::fs::read_to_string(&override_path).unwrap(), "###MINE\n###Personal tweaks\nHide\n\tCorrupted true\nImport \"base.filter\"\n");
+    /// let combined = Filter::read_from_file(override_path.to_str().unwrap()).unwrap();
+    /// # assert_eq!(combined.sections.len(), 2);
+    /// # assert_eq!(combined.sections[1].header, "BASE");
+    /// ```
+    pub fn write_with_overrides(
+        &self,
+        overrides: &Filter,
+        base_path: &str,
+        override_path: &str,
+    ) -> Result<(), Error> {
+        self.write_to_file(base_path)?;
+        let mut file = File::create(override_path)?;
+        let base = fs::canonicalize(base_path)?;
+        let directory = match Path::new(override_path).parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => fs::canonicalize(parent)?,
+            _ => fs::canonicalize(".")?,
+        };
+        let import = Import {
+            path: base
+                .strip_prefix(&directory)
+                .unwrap_or(&base)
+                .to_string_lossy()
+                .into_owned(),
+            optional: false,
+        };
+        write!(file, "{}", overrides)?;
+        writeln!(file, "{}", import)
+    }
+
+    /// Reads a filter from a file, replacing each `Import` with the blocks of the imported file.
+    ///
+    /// Missing `Optional` imports are skipped. A file that ends up importing itself is an error.
+    ///
+    /// # Example
+    /// ```
+    /// # use libfilter::filter::{Filter, LoadError};
+    /// let dir = std::env::temp_dir().join("libfilter_read_from_file");
+    /// std::fs::create_dir_all(&dir).unwrap();
+    /// std::fs::write(dir.join("a.filter"), "Import \"b.filter\"\nImport \"missing.filter\" Optional").unwrap();
+    /// std::fs::write(dir.join("b.filter"), "Import \"a.filter\"").unwrap();
+    ///
+    /// let error = Filter::read_from_file(dir.join("a.filter").to_str().unwrap()).unwrap_err();
+    /// # assert!(matches!(error, LoadError::Cycle(ref files) if files.len() == 3));
+    /// ```
+    pub fn read_from_file(path: &str) -> Result<Filter, LoadError> {
+        let items = load(Path::new(path), &mut vec![])?;
+        Ok(Filter::from_items(items))
+    }
+
+    /// Groups top level statements into sections.
+    ///
+    /// A block that follows an import starts an untitled section, so imports keep their place
+    /// between blocks.
+    fn from_items(items: Vec<Item>) -> Filter {
+        let mut filter = Filter::new();
+        let mut awaiting_description = false;
+        for item in items {
+            match item {
+                Item::Heading(text) => match filter.sections.last_mut() {
+                    Some(section) if awaiting_description => {
+                        section.description = text;
+                        awaiting_description = false;
+                    }
+                    _ => {
+                        filter.add_section(Section::new(text));
+                        awaiting_description = true;
+                    }
+                },
+                Item::Block(block) => {
+                    if !filter.last_section().imports.is_empty() {
+                        filter.add_section(Section::new(""));
+                    }
+                    filter.last_section().blocks.push(block);
+                    awaiting_description = false;
+                }
+                Item::Import(import) => {
+                    filter.last_section().imports.push(import);
+                    awaiting_description = false;
+                }
+            }
+        }
+        filter
+    }
+
+    /// The last section, adding an untitled one if there are none.
+    fn last_section(&mut self) -> &mut Section {
+        if self.sections.is_empty() {
+            self.add_section(Section::new(""));
+        }
+        self.sections.last_mut().unwrap()
+    }
+
+    /// Every block in the filter, in order.
+    pub fn blocks(&self) -> impl Iterator<Item = &Block> {
+        self.sections
+            .iter()
+            .flat_map(|section| section.blocks.iter())
+    }
+}
+
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         for section in &self.sections {
-            writeln!(file, "{}", section)?;
+            writeln!(f, "{}", section)?;
         }
         Ok(())
     }
 }
+
+/// Parses filter text without resolving imports. They are kept in [Section::imports], in the
+/// order they appear.
+///
+/// # Example
+/// ```
+/// # use libfilter::filter::Filter;
+/// let text = "###FFFF\n###Description\nShow\n\tAreaLevel > 1\n\tSetBorderColor 255 0 0 255\n";
+/// let filter = text.parse::<Filter>().unwrap();
+/// # assert_eq!(filter.to_string(), text);
+/// let layered = "Show\n\tClass \"Rings\"\nImport \"base.filter\"\nHide\n\tClass \"Belts\"\n";
+/// # assert_eq!(layered.parse::<Filter>().unwrap().to_string(), layered);
+/// let separated = "#######\n###0100\n###Currency\nShow\n\tClass \"Currency\"\n";
+/// let filter = separated.parse::<Filter>().unwrap();
+/// # assert_eq!(filter.sections.len(), 1);
+/// # assert_eq!(filter.sections[0].header, "0100");
+/// ```
+///
+/// Real filters parse too, with lines this library does not know kept as they were written:
+/// ```
+/// # use libfilter::filter::Filter;
+/// # use libfilter::line::Line;
+/// let neversink = r#"
+/// #===============================================================================================================
+/// ## NeverSink's Indepth Loot Filter - for Path of Exile
+/// #===============================================================================================================
+///
+/// Show # %D5 $type->currency $tier->t1exalted
+///     Class == "Stackable Currency"
+///     BaseType == "Exalted Orb" "Mirror of Kalandra"
+///     SetFontSize 45
+///     SetTextColor 255 0 0 255
+///     PlayAlertSound 6 300
+///     PlayEffect Red
+///     MinimapIcon 0 Red Star
+///
+/// Show # $type->influenced $tier->t1
+///     HasInfluence Crusader Elder Hunter Redeemer Shaper Warlord
+///     Rarity Normal Magic Rare
+///     ItemLevel >= 86
+///     PlayEffect White Temp
+///     MinimapIcon 1 White Circle
+///
+/// Show # $type->exotic $tier->veiled
+///     HasExplicitMod >=2 "Veil" "of the Veil"
+///     CustomAlertSound "sounds/veiled.mp3" 300
+///     Continue
+///
+/// Show # $type->clusters
+///     EnchantmentPassiveNode "Damage over Time Multiplier" "Critical Chance"
+///     TransfiguredGem False
+///     HasSearingExarchImplicit >= 1
+///
+/// Hide # %H3 $type->rest
+///     AreaLevel >= 68
+/// "#;
+/// let filter = neversink.parse::<Filter>().unwrap();
+/// # let blocks = filter.blocks().collect::<Vec<_>>();
+/// # assert_eq!(blocks.len(), 5);
+/// # assert_eq!(blocks[1].to_string(), "Show # $type->influenced $tier->t1\n\tHasInfluence Crusader Elder Hunter Redeemer Shaper Warlord\n\tRarity == Normal Magic Rare\n\tItemLevel >= 86\n\tPlayEffect White Temp\n\tMinimapIcon Medium White Circle");
+/// # assert_eq!(blocks[2].to_string(), "Show # $type->exotic $tier->veiled\n\tHasExplicitMod >=2 \"Veil\" \"of the Veil\"\n\tCustomAlertSound \"sounds/veiled.mp3\" 300\n\tContinue");
+/// # assert_eq!(blocks[3].lines[1], Line::Unknown(String::from("TransfiguredGem False")));
+/// # assert_eq!(blocks[3].lines[2], Line::Unknown(String::from("HasSearingExarchImplicit >= 1")));
+/// ```
+impl FromStr for Filter {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Filter::from_items(parse::items(s)?))
+    }
+}
+
+#[derive(Debug)]
+/// An error found while reading a filter from disk.
+pub enum LoadError {
+    /// A file could not be read.
+    Io(PathBuf, Error),
+
+    /// A file is not a valid filter.
+    Parse(PathBuf, ParseError),
+
+    /// The chain of imports that leads back to a file already being read.
+    Cycle(Vec<PathBuf>),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadError::Io(path, e) => write!(f, "{}: {}", path.display(), e),
+            LoadError::Parse(path, e) => write!(f, "{}: {}", path.display(), e),
+            LoadError::Cycle(paths) => {
+                write!(f, "import cycle: ")?;
+                for (index, path) in paths.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, " -> ")?;
+                    }
+                    write!(f, "{}", path.display())?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LoadError::Io(_, e) => Some(e),
+            LoadError::Parse(_, e) => Some(e),
+            LoadError::Cycle(_) => None,
+        }
+    }
+}
+
+/// Reads the statements of a file with its imports replaced by their statements.
+///
+/// `stack` holds the files currently being read, to detect cycles.
+fn load(path: &Path, stack: &mut Vec<PathBuf>) -> Result<Vec<Item>, LoadError> {
+    let canonical = fs::canonicalize(path).map_err(|e| LoadError::Io(path.to_path_buf(), e))?;
+    if stack.contains(&canonical) {
+        let mut cycle = stack.clone();
+        cycle.push(canonical);
+        return Err(LoadError::Cycle(cycle));
+    }
+    let text = fs::read_to_string(&canonical).map_err(|e| LoadError::Io(path.to_path_buf(), e))?;
+    let items = parse::items(&text).map_err(|e| LoadError::Parse(path.to_path_buf(), e))?;
+    let directory = canonical.parent().unwrap_or(Path::new("")).to_path_buf();
+
+    stack.push(canonical);
+    let mut resolved = vec![];
+    for item in items {
+        match item {
+            Item::Import(import) => {
+                let imported = directory.join(&import.path);
+                if import.optional && !imported.exists() {
+                    continue;
+                }
+                resolved.extend(load(&imported, stack)?);
+            }
+            item => resolved.push(item),
+        }
+    }
+    stack.pop();
+    Ok(resolved)
+}
@@ -0,0 +1,319 @@
+//! Canonical formatting of filters, so filters written by different tools produce the same text.
+use crate::block::Block;
+use crate::filter::Filter;
+use crate::line::condition::{self, Condition};
+use crate::line::Line;
+use crate::parse::Tokens;
+use itertools::Itertools;
+use std::fs::File;
+use std::io::{Error, Write};
+
+/// The order conditions are written in by [Order::Canonical].
+const CONDITION_ORDER: &[&str] = &condition::KEYWORDS;
+
+/// The order actions are written in by [Order::Canonical].
+const ACTION_ORDER: &[&str] = &[
+    "SetFontSize",
+    "SetTextColor",
+    "SetBorderColor",
+    "SetBackgroundColor",
+    "PlayAlertSound",
+    "PlayAlertSoundPositional",
+    "CustomAlertSound",
+    "DisableDroupSound",
+    "EnableDropSound",
+    "MinimapIcon",
+    "PlayEffect",
+    "Continue",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// How lines inside a block are indented.
+pub enum Indent {
+    /// A single tab.
+    Tab,
+
+    /// A number of spaces.
+    Spaces(usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The order lines of the same kind are written in.
+pub enum Order {
+    /// The order they were written in.
+    Preserve,
+
+    /// A fixed order by keyword.
+    Canonical,
+
+    /// These keywords first, in this order, then the rest in canonical order.
+    Custom(Vec<String>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// When values are quoted.
+pub enum Quoting {
+    /// Every text value is quoted.
+    Always,
+
+    /// Only values that would not read back the same unquoted are quoted: those that are empty
+    /// or contain whitespace, `#` or `"`.
+    WhenNeeded,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Options for [Filter::format].
+///
+/// The default indents with tabs, orders conditions and actions canonically, sorts `Class` and
+/// `BaseType` values, always quotes, separates blocks with a blank line and does not wrap.
+pub struct FormatOptions {
+    /// How lines inside a block are indented.
+    pub indent: Indent,
+
+    /// The order conditions are written in. Conditions always come before actions.
+    pub condition_order: Order,
+
+    /// The order actions are written in.
+    pub action_order: Order,
+
+    /// Sorts and removes duplicate `Class` and `BaseType` values.
+    pub sort_values: bool,
+
+    /// When values are quoted.
+    pub quoting: Quoting,
+
+    /// The longest a `BaseType` line may be. The game has no way to continue a line, so a block
+    /// with a longer line is split into copies that each match part of the list.
+    pub wrap_base_types: Option<usize>,
+
+    /// Separates blocks with a blank line.
+    pub blank_line_between_blocks: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> FormatOptions {
+        FormatOptions {
+            indent: Indent::Tab,
+            condition_order: Order::Canonical,
+            action_order: Order::Canonical,
+            sort_values: true,
+            quoting: Quoting::Always,
+            wrap_base_types: None,
+            blank_line_between_blocks: true,
+        }
+    }
+}
+
+impl Order {
+    /// Where a keyword sorts, or `None` to keep lines as written.
+    fn rank(&self, keyword: &str, canonical: &[&str]) -> Option<usize> {
+        let position = |keywords: &[&str]| {
+            keywords
+                .iter()
+                .position(|k| *k == keyword)
+                .unwrap_or(keywords.len())
+        };
+        match self {
+            Order::Preserve => None,
+            Order::Canonical => Some(position(canonical)),
+            Order::Custom(first) => match first.iter().position(|k| k == keyword) {
+                Some(index) => Some(index),
+                None => Some(first.len() + position(canonical)),
+            },
+        }
+    }
+}
+
+impl Filter {
+    /// Writes the filter as text laid out by the options.
+    ///
+    /// # Example
+    /// ```
+    /// # use libfilter::filter::Filter;
+    /// # use libfilter::format::{FormatOptions, Indent, Quoting};
+    /// let filter = "Show\n\tSetFontSize 45\n\tBaseType \"Vaal Orb\" \"Exalted Orb\" \"Vaal Orb\"\n\tClass \"Currency\"".parse::<Filter>().unwrap();
+    /// let options = FormatOptions {
+    ///     indent: Indent::Spaces(4),
+    ///     quoting: Quoting::WhenNeeded,
+    ///     ..FormatOptions::default()
+    /// };
+    /// # assert_eq!(filter.format(&options), "Show\n    Class Currency\n    BaseType \"Exalted Orb\" \"Vaal Orb\"\n    SetFontSize 45\n");
+    /// ```
+    ///
+    /// Wrapping splits a block with a long `BaseType` list:
+    /// ```
+    /// # use libfilter::filter::Filter;
+    /// # use libfilter::format::FormatOptions;
+    /// let filter = "Show\n\tBaseType \"Alpha\" \"Bravo\" \"Charlie\"\n\tSetFontSize 45".parse::<Filter>().unwrap();
+    /// let options = FormatOptions {
+    ///     wrap_base_types: Some(28),
+    ///     ..FormatOptions::default()
+    /// };
+    /// # assert_eq!(filter.format(&options), "Show\n\tBaseType \"Alpha\" \"Bravo\"\n\tSetFontSize 45\n\nShow\n\tBaseType \"Charlie\"\n\tSetFontSize 45\n");
+    /// let exact = "Show\n\tBaseType == \"Alpha\" \"Bravo\" \"Charlie\"".parse::<Filter>().unwrap();
+    /// let narrow = FormatOptions {
+    ///     wrap_base_types: Some(26),
+    ///     ..FormatOptions::default()
+    /// };
+    /// # assert_eq!(exact.format(&narrow), "Show\n\tBaseType == \"Alpha\"\n\nShow\n\tBaseType == \"Bravo\"\n\nShow\n\tBaseType == \"Charlie\"\n");
+    /// ```
+    ///
+    /// Values that would read back differently unquoted keep their quotes:
+    /// ```
+    /// # use libfilter::filter::Filter;
+    /// # use libfilter::format::{FormatOptions, Quoting};
+    /// let filter = "Show\n\tBaseType \"#1\" \"Vaal Orb\" Chaos".parse::<Filter>().unwrap();
+    /// let options = FormatOptions {
+    ///     quoting: Quoting::WhenNeeded,
+    ///     ..FormatOptions::default()
+    /// };
+    /// let text = filter.format(&options);
+    /// # assert_eq!(text, "Show\n\tBaseType \"#1\" Chaos \"Vaal Orb\"\n");
+    /// # assert_eq!(text.parse::<Filter>().unwrap().format(&options), text);
+    /// ```
+    pub fn format(&self, options: &FormatOptions) -> String {
+        let indent = match options.indent {
+            Indent::Tab => String::from("\t"),
+            Indent::Spaces(count) => " ".repeat(count),
+        };
+        let separator = if options.blank_line_between_blocks {
+            "\n"
+        } else {
+            ""
+        };
+        let mut text = String::new();
+        for section in &self.sections {
+            if section.is_titled() {
+                text.push_str(&format!(
+                    "###{}\n###{}\n",
+                    section.header, section.description
+                ));
+            }
+            let blocks = section
+                .blocks
+                .iter()
+                .flat_map(|block| normalize(block, options, indent.len()))
+                .map(|block| render(&block, options, &indent))
+                .collect::<Vec<_>>();
+            text.push_str(&blocks.join(separator));
+            for import in &section.imports {
+                text.push_str(&format!("{}\n", import));
+            }
+        }
+        text
+    }
+
+    /// Writes the filter to a file laid out by the options.
+    pub fn write_formatted(&self, path: &str, options: &FormatOptions) -> Result<(), Error> {
+        let mut file = File::create(path)?;
+        write!(file, "{}", self.format(options))
+    }
+}
+
+/// Sorts the lines and values of a block, splitting it if its `BaseType` line is too long.
+fn normalize(block: &Block, options: &FormatOptions, indent: usize) -> Vec<Block> {
+    let mut block = block.clone();
+    if options.sort_values {
+        for line in &mut block.lines {
+            if let Line::Condition(
+                Condition::Class((_, values)) | Condition::BaseType((_, values)),
+            ) = line
+            {
+                values.sort();
+                values.dedup();
+            }
+        }
+    }
+    block.lines.sort_by_key(|line| match line {
+        Line::Condition(condition) => (
+            0,
+            options
+                .condition_order
+                .rank(condition.keyword(), CONDITION_ORDER),
+        ),
+        Line::Action(action) => (1, options.action_order.rank(action.keyword(), ACTION_ORDER)),
+        Line::Unknown(_) => (2, None),
+    });
+    match options.wrap_base_types {
+        Some(width) => wrap(block, width, options, indent),
+        None => vec![block],
+    }
+}
+
+/// Splits a block into copies whose `BaseType` lines fit within `width`.
+fn wrap(block: Block, width: usize, options: &FormatOptions, indent: usize) -> Vec<Block> {
+    let position = block
+        .lines
+        .iter()
+        .position(|line| matches!(line, Line::Condition(Condition::BaseType(_))));
+    let (position, operator, values) = match position.map(|p| (p, &block.lines[p])) {
+        Some((p, Line::Condition(Condition::BaseType((operator, values))))) => {
+            (p, *operator, values.clone())
+        }
+        _ => return vec![block],
+    };
+    let mut chunks: Vec<Vec<String>> = vec![];
+    let mut length = 0;
+    for value in values {
+        let added = quote(&value, &options.quoting).len() + 1;
+        match chunks.last_mut() {
+            Some(chunk) if length + added <= width => chunk.push(value),
+            _ => {
+                length =
+                    indent + "BaseType".len() + operator.map_or(0, |op| op.to_string().len() + 1);
+                chunks.push(vec![value]);
+            }
+        }
+        length += added;
+    }
+    chunks
+        .into_iter()
+        .map(|chunk| {
+            let mut copy = block.clone();
+            copy.lines[position] = Line::Condition(Condition::BaseType((operator, chunk)));
+            copy
+        })
+        .collect()
+}
+
+/// Writes a block with the given indent and quoting.
+fn render(block: &Block, options: &FormatOptions, indent: &str) -> String {
+    let mut text = String::from(if block.is_hidden { "Hide" } else { "Show" });
+    if let Some(comment) = &block.comment {
+        text.push_str(&format!(" # {}", comment));
+    }
+    text.push('\n');
+    for line in &block.lines {
+        let line = match (line, &options.quoting) {
+            (Line::Unknown(line), _) => line.clone(),
+            (_, Quoting::Always) => line.to_string(),
+            (_, Quoting::WhenNeeded) => requote(&line.to_string()),
+        };
+        text.push_str(&format!("{}{}\n", indent, line));
+    }
+    text
+}
+
+/// Rewrites a line so only values that need it are quoted.
+fn requote(line: &str) -> String {
+    match Tokens::new(line).and_then(|mut tokens| tokens.strings()) {
+        Ok(tokens) => tokens
+            .iter()
+            .map(|token| quote(token, &Quoting::WhenNeeded))
+            .join(" "),
+        Err(_) => line.to_string(),
+    }
+}
+
+/// Quotes a value as the quoting asks.
+fn quote(value: &str, quoting: &Quoting) -> String {
+    match quoting {
+        Quoting::WhenNeeded
+            if !value.is_empty()
+                && !value.contains(|c: char| c.is_whitespace() || c == '#' || c == '"') =>
+        {
+            value.to_string()
+        }
+        _ => format!("\"{}\"", value),
+    }
+}
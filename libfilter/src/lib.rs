@@ -2,9 +2,14 @@
 //! A library for reading and writing Path of Exile filter files.
 
 pub mod block;
+pub mod builder;
 pub mod filter;
+pub mod format;
 pub mod line;
+pub mod parse;
+pub mod patch;
 pub mod section;
+pub mod selector;
 
 // Re-exports
 pub use crate::line::color::Color;
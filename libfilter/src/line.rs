@@ -4,15 +4,18 @@ pub mod action;
 pub mod color;
 pub mod condition;
 pub mod gem_quality;
+pub mod import;
 pub mod influence;
 pub mod operator;
 pub mod rarity;
 pub mod rgba;
 pub mod shape;
 pub mod size;
+use crate::parse::{ParseError, Tokens};
 use std::fmt;
+use std::str::FromStr;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 /// The lowest component of a filter
 ///
 /// A line is either a condition or an action
@@ -34,6 +37,7 @@ use std::fmt;
 /// # use libfilter::line::Line;
 /// let line = Line::Action(Action::SetFontSize(16));
 /// # assert_eq!(line.to_string(), "SetFontSize 16");
+/// # assert_eq!("SetFontSize 16".parse::<Line>().unwrap(), line);
 /// ```
 pub enum Line {
     /// A condition is used as a test to filter items
@@ -41,13 +45,41 @@ pub enum Line {
 
     /// An action is what happens when a condition matches an item
     Action(action::Action),
+
+    /// A line whose keyword this library does not know, kept as it was written
+    ///
+    /// # Example
+    /// ```
+    /// # use libfilter::line::Line;
+    /// let line = "TransfiguredGem False".parse::<Line>().unwrap();
+    /// # assert_eq!(line, Line::Unknown(String::from("TransfiguredGem False")));
+    /// # assert_eq!(line.to_string(), "TransfiguredGem False");
+    /// ```
+    Unknown(String),
 }
 
 impl fmt::Display for Line {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Line::Condition(condition) => write!(f, "{}", condition.to_string()),
-            Line::Action(action) => write!(f, "{}", action.to_string()),
+            Line::Condition(condition) => write!(f, "{}", condition),
+            Line::Action(action) => write!(f, "{}", action),
+            Line::Unknown(line) => write!(f, "{}", line),
+        }
+    }
+}
+
+impl FromStr for Line {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tokens = Tokens::new(s)?;
+        match tokens.peek() {
+            Some(keyword) if action::KEYWORDS.contains(&keyword) => Ok(Line::Action(s.parse()?)),
+            Some(keyword) if condition::KEYWORDS.contains(&keyword) => {
+                Ok(Line::Condition(s.parse()?))
+            }
+            Some(_) => Ok(Line::Unknown(s.trim().to_string())),
+            None => Err(ParseError::new("missing keyword")),
         }
     }
 }
@@ -4,9 +4,11 @@ use crate::line::color::Color;
 use crate::line::rgba::RGBA;
 use crate::line::shape::Shape;
 use crate::line::size::Size;
+use crate::parse::{ParseError, Tokens};
 use std::fmt;
+use std::str::FromStr;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 /// Actions that a line can specify
 pub enum Action {
     /// Set the border color
@@ -103,13 +105,17 @@ pub enum Action {
     /// Set the custom alert sound
     ///
     /// `String` is the path to the sound
+    /// `Option<u16>` is the sound volume, or `None` for the default
     /// Example:
     /// ```
     /// # use libfilter::line::action::Action;
-    /// let custom_alert_sound = Action::CustomAlertSound(String::from("/path/to/sound.wav"));
+    /// let custom_alert_sound = Action::CustomAlertSound((String::from("/path/to/sound.wav"), None));
     /// # assert_eq!(custom_alert_sound.to_string(), "CustomAlertSound \"/path/to/sound.wav\"");
+    /// let loud = "CustomAlertSound \"sound.wav\" 300".parse::<Action>().unwrap();
+    /// # assert_eq!(loud, Action::CustomAlertSound((String::from("sound.wav"), Some(300))));
+    /// # assert_eq!(loud.to_string(), "CustomAlertSound \"sound.wav\" 300");
     /// ```
-    CustomAlertSound(String),
+    CustomAlertSound((String, Option<u16>)),
 
     /// Set the minimap icon
     ///
@@ -124,21 +130,80 @@ pub enum Action {
     /// # use libfilter::Shape;
     /// let minimap_icon = Action::MinimapIcon((Size::Small, Color::Red, Shape::Circle));
     /// # assert_eq!(minimap_icon.to_string(), "MinimapIcon Small Red Circle");
+    /// # assert_eq!("MinimapIcon 2 Red Circle".parse::<Action>().unwrap(), minimap_icon);
     /// ```
     MinimapIcon((Size, Color, Shape)),
 
     /// Play an effect
     ///
     /// `Color` is the color
-    /// `bool` is whether the beam is temporary
+    /// `bool` is whether the beam is temporary, written as `Temp`
     /// Example:
     /// ```
     /// # use libfilter::line::action::Action;
     /// # use libfilter::Color;
     /// let play_effect = Action::PlayEffect((Color::Red, true));
-    /// # assert_eq!(play_effect.to_string(), "PlayEffect Red true");
+    /// # assert_eq!(play_effect.to_string(), "PlayEffect Red Temp");
+    /// # assert_eq!(Action::PlayEffect((Color::Red, false)).to_string(), "PlayEffect Red");
+    /// # assert_eq!("PlayEffect Red Temp".parse::<Action>().unwrap(), play_effect);
+    /// # assert_eq!("PlayEffect Red".parse::<Action>().unwrap(), Action::PlayEffect((Color::Red, false)));
     /// ```
     PlayEffect((Color, bool)),
+
+    /// Keep matching later blocks after this one, so their actions are applied on top
+    ///
+    /// Example:
+    /// ```
+    /// # use libfilter::line::action::Action;
+    /// let continue_matching = Action::Continue;
+    /// # assert_eq!(continue_matching.to_string(), "Continue");
+    /// ```
+    Continue,
+}
+
+/// The keyword of every action, with the misspelled `DisableDroupSound` and its correct
+/// spelling both accepted.
+pub const KEYWORDS: [&str; 13] = [
+    "SetBorderColor",
+    "SetTextColor",
+    "SetBackgroundColor",
+    "SetFontSize",
+    "PlayAlertSound",
+    "PlayAlertSoundPositional",
+    "DisableDroupSound",
+    "DisableDropSound",
+    "EnableDropSound",
+    "CustomAlertSound",
+    "MinimapIcon",
+    "PlayEffect",
+    "Continue",
+];
+
+impl Action {
+    /// The keyword that starts the action.
+    ///
+    /// # Example
+    /// ```
+    /// # use libfilter::line::action::Action;
+    /// let set_font_size = Action::SetFontSize(16);
+    /// # assert_eq!(set_font_size.keyword(), "SetFontSize");
+    /// ```
+    pub fn keyword(&self) -> &'static str {
+        match self {
+            Action::SetBorderColor(_) => "SetBorderColor",
+            Action::SetTextColor(_) => "SetTextColor",
+            Action::SetBackgroundColor(_) => "SetBackgroundColor",
+            Action::SetFontSize(_) => "SetFontSize",
+            Action::PlayAlertSound(_) => "PlayAlertSound",
+            Action::PlayAlertSoundPositional(_) => "PlayAlertSoundPositional",
+            Action::DisableDroupSound => "DisableDroupSound",
+            Action::EnableDropSound => "EnableDropSound",
+            Action::CustomAlertSound(_) => "CustomAlertSound",
+            Action::MinimapIcon(_) => "MinimapIcon",
+            Action::PlayEffect(_) => "PlayEffect",
+            Action::Continue => "Continue",
+        }
+    }
 }
 
 impl fmt::Display for Action {
@@ -154,15 +219,77 @@ impl fmt::Display for Action {
             }
             Action::DisableDroupSound => write!(f, "DisableDroupSound"),
             Action::EnableDropSound => write!(f, "EnableDropSound"),
-            Action::CustomAlertSound(sound_path) => {
+            Action::CustomAlertSound((sound_path, None)) => {
                 write!(f, "CustomAlertSound \"{}\"", sound_path)
             }
+            Action::CustomAlertSound((sound_path, Some(volume))) => {
+                write!(f, "CustomAlertSound \"{}\" {}", sound_path, volume)
+            }
             Action::MinimapIcon(fields) => {
                 write!(f, "MinimapIcon {} {} {}", fields.0, fields.1, fields.2)
             }
-            Action::PlayEffect(fields) => {
-                write!(f, "PlayEffect {} {}", fields.0, fields.1)
-            }
+            Action::PlayEffect((color, false)) => write!(f, "PlayEffect {}", color),
+            Action::PlayEffect((color, true)) => write!(f, "PlayEffect {} Temp", color),
+            Action::Continue => write!(f, "Continue"),
         }
     }
 }
+
+impl FromStr for Action {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tokens = Tokens::new(s)?;
+        let keyword = tokens.string()?;
+        let action = match keyword.as_str() {
+            "SetBorderColor" => Action::SetBorderColor(rgba(&mut tokens)?),
+            "SetTextColor" => Action::SetTextColor(rgba(&mut tokens)?),
+            "SetBackgroundColor" => Action::SetBackgroundColor(rgba(&mut tokens)?),
+            "SetFontSize" => Action::SetFontSize(tokens.value()?),
+            "PlayAlertSound" => Action::PlayAlertSound((tokens.value()?, tokens.value()?)),
+            "PlayAlertSoundPositional" => {
+                Action::PlayAlertSoundPositional((tokens.value()?, tokens.value()?))
+            }
+            "DisableDroupSound" | "DisableDropSound" => Action::DisableDroupSound,
+            "EnableDropSound" => Action::EnableDropSound,
+            "CustomAlertSound" => {
+                let path = tokens.string()?;
+                let volume = match tokens.is_empty() {
+                    true => None,
+                    false => Some(tokens.value()?),
+                };
+                Action::CustomAlertSound((path, volume))
+            }
+            "MinimapIcon" => {
+                Action::MinimapIcon((tokens.value()?, tokens.value()?, tokens.value()?))
+            }
+            "PlayEffect" => {
+                let color = tokens.value()?;
+                let temporary = match tokens.peek() {
+                    None => false,
+                    Some("Temp") => {
+                        tokens.string()?;
+                        true
+                    }
+                    Some(_) => tokens.boolean()?,
+                };
+                Action::PlayEffect((color, temporary))
+            }
+            "Continue" => Action::Continue,
+            _ => return Err(ParseError::new(format!("unknown action `{}`", keyword))),
+        };
+        tokens.finish()?;
+        Ok(action)
+    }
+}
+
+/// Reads the red, green, blue and alpha values of a color. Alpha defaults to 255.
+fn rgba(tokens: &mut Tokens) -> Result<RGBA, ParseError> {
+    let (r, g, b) = (tokens.value()?, tokens.value()?, tokens.value()?);
+    let a = if tokens.is_empty() {
+        255
+    } else {
+        tokens.value()?
+    };
+    Ok(RGBA { r, g, b, a })
+}
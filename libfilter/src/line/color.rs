@@ -1,6 +1,8 @@
 //! Predefined colors for the minimap icon and light beam
+use crate::parse::ParseError;
 use std::fmt;
-#[derive(Debug)]
+use std::str::FromStr;
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 /// Predefined colors for the minimap icon and light beam
 pub enum Color {
     /// Red
@@ -142,3 +144,25 @@ impl fmt::Display for Color {
         }
     }
 }
+
+impl FromStr for Color {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Red" => Ok(Color::Red),
+            "Green" => Ok(Color::Green),
+            "Blue" => Ok(Color::Blue),
+            "Brown" => Ok(Color::Brown),
+            "White" => Ok(Color::White),
+            "Yellow" => Ok(Color::Yellow),
+            "Cyan" => Ok(Color::Cyan),
+            "Grey" => Ok(Color::Grey),
+            "Orange" => Ok(Color::Orange),
+            "Pink" => Ok(Color::Pink),
+            "Purple" => Ok(Color::Purple),
+            "None" => Ok(Color::None),
+            _ => Err(ParseError::new(format!("unknown color `{}`", s))),
+        }
+    }
+}
@@ -4,8 +4,11 @@ use crate::line::gem_quality::GemQuality;
 use crate::line::influence::Influence;
 use crate::line::operator::Operator;
 use crate::line::rarity::Rarity;
+use crate::parse::{ParseError, Tokens};
+use itertools::Itertools;
 use std::fmt;
-#[derive(Debug)]
+use std::str::FromStr;
+#[derive(Debug, Clone, PartialEq, Eq)]
 /// Conditions are used to determine if an item should be shown or hidden or have an [crate::line::action::Action] applied to it
 pub enum Condition {
     /// Filters for items dropped in a particular Monster level of the current area.
@@ -61,36 +64,53 @@ pub enum Condition {
     /// The [Rarity] of the item.
     ///
     /// `Operator`: A value from the [Operator] enum.
-    /// `Rarity`: The [Rarity] to test.
+    /// `Vec<Rarity>`: The rarities to test. Any of them may match.
     /// # Example
     /// ```
     /// # use libfilter::{Operator, Rarity};
     /// # use libfilter::line::condition::Condition;
-    /// let rarity = Condition::Rarity((Operator::GreaterThan, Rarity::Normal));
+    /// let rarity = Condition::Rarity((Operator::GreaterThan, vec![Rarity::Normal]));
     /// # assert_eq!(rarity.to_string(), "Rarity > Normal");
+    /// let rarities = "Rarity Normal Magic".parse::<Condition>().unwrap();
+    /// # assert_eq!(rarities, Condition::Rarity((Operator::Equal, vec![Rarity::Normal, Rarity::Magic])));
+    /// # assert_eq!(rarities.to_string(), "Rarity == Normal Magic");
     /// ```
-    Rarity((Operator, Rarity)),
+    Rarity((Operator, Vec<Rarity>)),
 
     /// The item class. Specifying part of a class name is allowed and will match any classes with that text in the name.
     ///
-    /// `String`: The name of the class to match.
+    /// `Option<Operator>`: `==` to match whole names only, or `None` to match parts of names.
+    /// `Vec<String>`: The names of the classes to match. Any of them may match.
     /// # Example
     /// ```
+    /// # use libfilter::Operator;
     /// # use libfilter::line::condition::Condition;
-    /// let class = Condition::Class(String::from("One Handed"));
+    /// let class = Condition::Class((None, vec![String::from("One Handed")]));
     /// # assert_eq!(class.to_string(), "Class \"One Handed\"");
-    Class(String),
+    /// let classes = Condition::Class((None, vec![String::from("Rings"), String::from("Amulets")]));
+    /// # assert_eq!(classes.to_string(), "Class \"Rings\" \"Amulets\"");
+    /// # assert_eq!("Class \"Rings\" \"Amulets\"".parse::<Condition>().unwrap(), classes);
+    /// let exact = Condition::Class((Some(Operator::Equal), vec![String::from("Rings")]));
+    /// # assert_eq!("Class == \"Rings\"".parse::<Condition>().unwrap(), exact);
+    /// # assert_eq!(exact.to_string(), "Class == \"Rings\"");
+    /// ```
+    Class((Option<Operator>, Vec<String>)),
 
     /// The base type of the item. Specifying a part of a base type name is allowed and will match any of the base types with that text in the name.
     ///
-    /// `String`: The name of the base type to match.
+    /// `Option<Operator>`: `==` to match whole names only, or `None` to match parts of names.
+    /// `Vec<String>`: The names of the base types to match. Any of them may match.
     /// # Example
     /// ```
+    /// # use libfilter::Operator;
     /// # use libfilter::line::condition::Condition;
-    /// let base_type = Condition::BaseType(String::from("Iron Ring"));
+    /// let base_type = Condition::BaseType((None, vec![String::from("Iron Ring")]));
     /// # assert_eq!(base_type.to_string(), "BaseType \"Iron Ring\"");
+    /// let exact = "BaseType == \"Mirror of Kalandra\"".parse::<Condition>().unwrap();
+    /// # assert_eq!(exact, Condition::BaseType((Some(Operator::Equal), vec![String::from("Mirror of Kalandra")])));
+    /// # assert_eq!(exact.to_string(), "BaseType == \"Mirror of Kalandra\"");
     /// ```
-    BaseType(String),
+    BaseType((Option<Operator>, Vec<String>)),
 
     /// The prophecy name. Specifying a part of a prophecy name is allowed and will match any of the prophecies with that text in the name. Prophecies have the Class type "Stackable Currency".
     ///
@@ -118,31 +138,36 @@ pub enum Condition {
 
     /// Supports a list of groups that each one represents linked sockets containing a specific set of colors, at least one group must be matched for the condition to pass.
     ///
-    /// `Operator`: A value from the [Operator] enum.
+    /// `Option<Operator>`: A value from the [Operator] enum, or `None` if the line has none.
     /// `String`: The color sequence to test.
-    /// `u8`: The longest link on the item.
+    /// `u8`: The longest link on the item, or 0 if the line only gives colors.
     /// # Example
     /// ```
     /// # use libfilter::Operator;
     /// # use libfilter::line::condition::Condition;
-    /// let socket_group = Condition::SocketGroup((Operator::GreaterThanOrEqual, String::from("GGG"), 5));
+    /// let socket_group = Condition::SocketGroup((Some(Operator::GreaterThanOrEqual), String::from("GGG"), 5));
     /// # assert_eq!(socket_group.to_string(), "SocketGroup >= 5GGG");
+    /// # assert_eq!("SocketGroup >= 5GGG".parse::<Condition>().unwrap(), socket_group);
+    /// let colors = "SocketGroup \"RGB\"".parse::<Condition>().unwrap();
+    /// # assert_eq!(colors, Condition::SocketGroup((None, String::from("RGB"), 0)));
+    /// # assert_eq!(colors.to_string(), "SocketGroup RGB");
     /// ```
-    SocketGroup((Operator, String, u8)),
+    SocketGroup((Option<Operator>, String, u8)),
 
     /// Does the exact same thing as [SocketGroup] but does not require the sockets to be linked.
     ///
-    /// `Operator`: A value from the [Operator] enum.
+    /// `Option<Operator>`: A value from the [Operator] enum, or `None` if the line has none.
     /// `String`: The color sequence to test.
-    /// `u8`: The number of sockets on the item.
+    /// `u8`: The number of sockets on the item, or 0 if the line only gives colors.
     /// # Example
     /// ```
     /// # use libfilter::Operator;
     /// # use libfilter::line::condition::Condition;
-    /// let sockets = Condition::Sockets((Operator::GreaterThanOrEqual, String::from("GGG"), 5));
+    /// let sockets = Condition::Sockets((Some(Operator::GreaterThanOrEqual), String::from("GGG"), 5));
     /// # assert_eq!(sockets.to_string(), "Sockets >= 5GGG");
+    /// # assert_eq!("Sockets 6".parse::<Condition>().unwrap().to_string(), "Sockets 6");
     /// ```
-    Sockets((Operator, String, u8)),
+    Sockets((Option<Operator>, String, u8)),
 
     /// The number of slots the item takes on the Y-axis (verical axis), i.e. the height of the item.
     ///
@@ -172,14 +197,21 @@ pub enum Condition {
 
     /// Filter by mods on an item by name.
     ///
-    /// `String`: The name of the mod to match.
+    /// `Option<Operator>`: A value from the [Operator] enum, or `None` if the line has none.
+    /// `Option<u8>`: How many of the mods the item must have, or `None` for any of them.
+    /// `Vec<String>`: The names of the mods to match.
     /// # Example
     /// ```
+    /// # use libfilter::Operator;
     /// # use libfilter::line::condition::Condition;
-    /// let mod_name = Condition::HasExplicitMod(String::from("Tyrannical"));
+    /// let mod_name = Condition::HasExplicitMod((None, None, vec![String::from("Tyrannical")]));
     /// # assert_eq!(mod_name.to_string(), "HasExplicitMod \"Tyrannical\"");
+    /// let counted = "HasExplicitMod >=2 \"Veil\" \"of the Veil\"".parse::<Condition>().unwrap();
+    /// # assert_eq!(counted, Condition::HasExplicitMod((Some(Operator::GreaterThanOrEqual), Some(2), vec![String::from("Veil"), String::from("of the Veil")])));
+    /// # assert_eq!(counted.to_string(), "HasExplicitMod >=2 \"Veil\" \"of the Veil\"");
+    /// # assert_eq!("HasExplicitMod >= 2 \"Veil\"".parse::<Condition>().unwrap().to_string(), "HasExplicitMod >=2 \"Veil\"");
     /// ```
-    HasExplicitMod(String),
+    HasExplicitMod((Option<Operator>, Option<u8>, Vec<String>)),
 
     /// If an item has any enchantment from the Labyrinth.
     ///
@@ -205,14 +237,16 @@ pub enum Condition {
 
     /// Filter Cluster Jewels by enchantment type.
     ///
-    /// `String`: The name of the enchantment to match.
+    /// `Option<Operator>`: `==` to match whole names only, or `None` to match parts of names.
+    /// `Vec<String>`: The names of the enchantments to match. Any of them may match.
     /// # Example
     /// ```
     /// # use libfilter::line::condition::Condition;
-    /// let enchantment = Condition::EnchantmentPassiveNode(String::from("increased Damage with Two Handed Weapons"));
+    /// let enchantment = Condition::EnchantmentPassiveNode((None, vec![String::from("increased Damage with Two Handed Weapons")]));
     /// # assert_eq!(enchantment.to_string(), "EnchantmentPassiveNode \"increased Damage with Two Handed Weapons\"");
+    /// # assert_eq!("EnchantmentPassiveNode \"Critical Chance\" \"Curse Effect\"".parse::<Condition>().unwrap().to_string(), "EnchantmentPassiveNode \"Critical Chance\" \"Curse Effect\"");
     /// ```
-    EnchantmentPassiveNode(String),
+    EnchantmentPassiveNode((Option<Operator>, Vec<String>)),
 
     /// Filter Cluster Jewels by the number of enchantments. Only checks the "Adds X passive skills" modifier.
     ///
@@ -357,15 +391,19 @@ pub enum Condition {
 
     /// If an item has an [Influence]
     ///
-    /// `Influence`: A value from the [Influence] enum
+    /// `Option<Operator>`: `==` if the item must have every influence, or `None` for any of them.
+    /// `Vec<Influence>`: Values from the [Influence] enum
     /// # Example
     /// ```
     /// # use libfilter::Influence;
     /// # use libfilter::line::condition::Condition;
-    /// let influence = Condition::HasInfluence(Influence::Shaper);
+    /// let influence = Condition::HasInfluence((None, vec![Influence::Shaper]));
     /// # assert_eq!(influence.to_string(), "HasInfluence Shaper");
+    /// let any = "HasInfluence Crusader Elder".parse::<Condition>().unwrap();
+    /// # assert_eq!(any, Condition::HasInfluence((None, vec![Influence::Crusader, Influence::Elder])));
+    /// # assert_eq!(any.to_string(), "HasInfluence Crusader Elder");
     /// ```
-    HasInfluence(Influence),
+    HasInfluence((Option<Operator>, Vec<Influence>)),
 
     /// If an item is fractured or not.
     ///
@@ -414,6 +452,17 @@ pub enum Condition {
     /// ```
     BlightedMap(bool),
 
+    /// If the map is Blight-ravaged or not.
+    ///
+    /// `bool`: If the map is Blight-ravaged.
+    /// # Example
+    /// ```
+    /// # use libfilter::line::condition::Condition;
+    /// let uber_blighted_map = Condition::UberBlightedMap(true);
+    /// # assert_eq!(uber_blighted_map.to_string(), "UberBlightedMap true");
+    /// ```
+    UberBlightedMap(bool),
+
     /// The map tier of the map.
     ///
     /// `Operator`: A value from the [Operator] enum.
@@ -428,6 +477,100 @@ pub enum Condition {
     MapTier((Operator, u8)),
 }
 
+/// The keyword of every condition, in the order of [Condition].
+pub const KEYWORDS: [&str; 37] = [
+    "AreaLevel",
+    "ItemLevel",
+    "DropLevel",
+    "Quality",
+    "Rarity",
+    "Class",
+    "BaseType",
+    "Prophecy",
+    "LinkedSockets",
+    "SocketGroup",
+    "Sockets",
+    "Height",
+    "Width",
+    "HasExplicitMod",
+    "AnyEnchantment",
+    "HasEnchantment",
+    "EnchantmentPassiveNode",
+    "EnchantmentPassiveNum",
+    "StackSize",
+    "GemLevel",
+    "GemQualityType",
+    "AlternativeQuality",
+    "Replica",
+    "Identified",
+    "Corrupted",
+    "CorruptedMods",
+    "Mirrored",
+    "ElderItem",
+    "ShaperItem",
+    "HasInfluence",
+    "FracturedItem",
+    "SynthesisedItem",
+    "ElderMap",
+    "ShapedMap",
+    "BlightedMap",
+    "UberBlightedMap",
+    "MapTier",
+];
+
+impl Condition {
+    /// The keyword that starts the condition.
+    ///
+    /// # Example
+    /// ```
+    /// # use libfilter::Operator;
+    /// # use libfilter::line::condition::Condition;
+    /// let item_level = Condition::ItemLevel((Operator::GreaterThan, 1));
+    /// # assert_eq!(item_level.keyword(), "ItemLevel");
+    /// ```
+    pub fn keyword(&self) -> &'static str {
+        match self {
+            Condition::AreaLevel(_) => "AreaLevel",
+            Condition::ItemLevel(_) => "ItemLevel",
+            Condition::DropLevel(_) => "DropLevel",
+            Condition::Quality(_) => "Quality",
+            Condition::Rarity(_) => "Rarity",
+            Condition::Class(_) => "Class",
+            Condition::BaseType(_) => "BaseType",
+            Condition::Prophecy(_) => "Prophecy",
+            Condition::LinkedSockets(_) => "LinkedSockets",
+            Condition::SocketGroup(_) => "SocketGroup",
+            Condition::Sockets(_) => "Sockets",
+            Condition::Height(_) => "Height",
+            Condition::Width(_) => "Width",
+            Condition::HasExplicitMod(_) => "HasExplicitMod",
+            Condition::AnyEnchantment(_) => "AnyEnchantment",
+            Condition::HasEnchantment(_) => "HasEnchantment",
+            Condition::EnchantmentPassiveNode(_) => "EnchantmentPassiveNode",
+            Condition::EnchantmentPassiveNum(_) => "EnchantmentPassiveNum",
+            Condition::StackSize(_) => "StackSize",
+            Condition::GemLevel(_) => "GemLevel",
+            Condition::GemQualityType(_) => "GemQualityType",
+            Condition::AlternativeQuality(_) => "AlternativeQuality",
+            Condition::Replica(_) => "Replica",
+            Condition::Identified(_) => "Identified",
+            Condition::Corrupted(_) => "Corrupted",
+            Condition::CorruptedMods(_) => "CorruptedMods",
+            Condition::Mirrored(_) => "Mirrored",
+            Condition::ElderItem(_) => "ElderItem",
+            Condition::ShaperItem(_) => "ShaperItem",
+            Condition::HasInfluence(_) => "HasInfluence",
+            Condition::FracturedItem(_) => "FracturedItem",
+            Condition::SynthesisedItem(_) => "SynthesisedItem",
+            Condition::ElderMap(_) => "ElderMap",
+            Condition::ShapedMap(_) => "ShapedMap",
+            Condition::BlightedMap(_) => "BlightedMap",
+            Condition::UberBlightedMap(_) => "UberBlightedMap",
+            Condition::MapTier(_) => "MapTier",
+        }
+    }
+}
+
 impl fmt::Display for Condition {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -435,22 +578,30 @@ impl fmt::Display for Condition {
             Condition::ItemLevel((op, val)) => write!(f, "ItemLevel {} {}", op, val),
             Condition::DropLevel((op, val)) => write!(f, "DropLevel {} {}", op, val),
             Condition::Quality((op, quality)) => write!(f, "Quality {} {}", op, quality),
-            Condition::Rarity((op, rarity)) => write!(f, "Rarity {} {}", op, rarity),
-            Condition::Class(val) => write!(f, "Class \"{}\"", val),
-            Condition::BaseType(val) => write!(f, "BaseType \"{}\"", val),
+            Condition::Rarity((op, rarities)) => {
+                write!(f, "Rarity {} {}", op, rarities.iter().join(" "))
+            }
+            Condition::Class((op, val)) => write!(f, "Class {}{}", prefix(op), quoted(val)),
+            Condition::BaseType((op, val)) => {
+                write!(f, "BaseType {}{}", prefix(op), quoted(val))
+            }
             Condition::Prophecy(val) => write!(f, "Prophecy \"{}\"", val),
             Condition::LinkedSockets((op, val)) => write!(f, "LinkedSockets {} {}", op, val),
             Condition::SocketGroup((op, val, count)) => {
-                write!(f, "SocketGroup {} {}{}", op, count, val)
+                write!(f, "SocketGroup {}{}", prefix(op), socket_value(*count, val))
+            }
+            Condition::Sockets((op, val, count)) => {
+                write!(f, "Sockets {}{}", prefix(op), socket_value(*count, val))
             }
-            Condition::Sockets((op, val, count)) => write!(f, "Sockets {} {}{}", op, count, val),
             Condition::Height((op, val)) => write!(f, "Height {} {}", op, val),
             Condition::Width((op, val)) => write!(f, "Width {} {}", op, val),
-            Condition::HasExplicitMod(val) => write!(f, "HasExplicitMod \"{}\"", val),
+            Condition::HasExplicitMod((op, count, val)) => {
+                write!(f, "HasExplicitMod {}{}", counted(op, count), quoted(val))
+            }
             Condition::AnyEnchantment(val) => write!(f, "AnyEnchantment {}", val),
             Condition::HasEnchantment(val) => write!(f, "HasEnchantment \"{}\"", val),
-            Condition::EnchantmentPassiveNode(val) => {
-                write!(f, "EnchantmentPassiveNode \"{}\"", val)
+            Condition::EnchantmentPassiveNode((op, val)) => {
+                write!(f, "EnchantmentPassiveNode {}{}", prefix(op), quoted(val))
             }
             Condition::EnchantmentPassiveNum((op, val)) => {
                 write!(f, "EnchantmentPassiveNum {} {}", op, val)
@@ -466,13 +617,170 @@ impl fmt::Display for Condition {
             Condition::Mirrored(val) => write!(f, "Mirrored {}", val),
             Condition::ElderItem(val) => write!(f, "ElderItem {}", val),
             Condition::ShaperItem(val) => write!(f, "ShaperItem {}", val),
-            Condition::HasInfluence(val) => write!(f, "HasInfluence {}", val),
+            Condition::HasInfluence((op, val)) => {
+                write!(f, "HasInfluence {}{}", prefix(op), val.iter().join(" "))
+            }
             Condition::FracturedItem(val) => write!(f, "FracturedItem {}", val),
             Condition::SynthesisedItem(val) => write!(f, "SynthesisedItem {}", val),
             Condition::ElderMap(val) => write!(f, "ElderMap {}", val),
             Condition::ShapedMap(val) => write!(f, "ShapedMap {}", val),
             Condition::BlightedMap(val) => write!(f, "BlightedMap {}", val),
+            Condition::UberBlightedMap(val) => write!(f, "UberBlightedMap {}", val),
             Condition::MapTier((op, val)) => write!(f, "MapTier {} {}", op, val),
         }
     }
 }
+
+impl FromStr for Condition {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tokens = Tokens::new(s)?;
+        let keyword = tokens.string()?;
+        let condition = match keyword.as_str() {
+            "AreaLevel" => Condition::AreaLevel((tokens.operator(), tokens.value()?)),
+            "ItemLevel" => Condition::ItemLevel((tokens.operator(), tokens.value()?)),
+            "DropLevel" => Condition::DropLevel((tokens.operator(), tokens.value()?)),
+            "Quality" => Condition::Quality((tokens.operator(), tokens.value()?)),
+            "Rarity" => Condition::Rarity((tokens.operator(), tokens.values()?)),
+            "Class" => Condition::Class((tokens.optional_operator(), tokens.strings()?)),
+            "BaseType" => Condition::BaseType((tokens.optional_operator(), tokens.strings()?)),
+            "Prophecy" => Condition::Prophecy(tokens.string()?),
+            "LinkedSockets" => Condition::LinkedSockets((tokens.operator(), tokens.value()?)),
+            "SocketGroup" => {
+                let operator = tokens.optional_operator();
+                let (count, colors) = sockets(&tokens.string()?)?;
+                Condition::SocketGroup((operator, colors, count))
+            }
+            "Sockets" => {
+                let operator = tokens.optional_operator();
+                let (count, colors) = sockets(&tokens.string()?)?;
+                Condition::Sockets((operator, colors, count))
+            }
+            "Height" => Condition::Height((tokens.operator(), tokens.value()?)),
+            "Width" => Condition::Width((tokens.operator(), tokens.value()?)),
+            "HasExplicitMod" => {
+                let (operator, count) = count(&mut tokens)?;
+                Condition::HasExplicitMod((operator, count, tokens.strings()?))
+            }
+            "AnyEnchantment" => Condition::AnyEnchantment(tokens.boolean()?),
+            "HasEnchantment" => Condition::HasEnchantment(tokens.string()?),
+            "EnchantmentPassiveNode" => {
+                Condition::EnchantmentPassiveNode((tokens.optional_operator(), tokens.strings()?))
+            }
+            "EnchantmentPassiveNum" => {
+                Condition::EnchantmentPassiveNum((tokens.operator(), tokens.value()?))
+            }
+            "StackSize" => Condition::StackSize((tokens.operator(), tokens.value()?)),
+            "GemLevel" => Condition::GemLevel((tokens.operator(), tokens.value()?)),
+            "GemQualityType" => Condition::GemQualityType(tokens.value()?),
+            "AlternativeQuality" => Condition::AlternativeQuality(tokens.boolean()?),
+            "Replica" => Condition::Replica(tokens.boolean()?),
+            "Identified" => Condition::Identified(tokens.boolean()?),
+            "Corrupted" => Condition::Corrupted(tokens.boolean()?),
+            "CorruptedMods" => Condition::CorruptedMods((tokens.operator(), tokens.value()?)),
+            "Mirrored" => Condition::Mirrored(tokens.boolean()?),
+            "ElderItem" => Condition::ElderItem(tokens.boolean()?),
+            "ShaperItem" => Condition::ShaperItem(tokens.boolean()?),
+            "HasInfluence" => {
+                Condition::HasInfluence((tokens.optional_operator(), tokens.values()?))
+            }
+            "FracturedItem" => Condition::FracturedItem(tokens.boolean()?),
+            "SynthesisedItem" => Condition::SynthesisedItem(tokens.boolean()?),
+            "ElderMap" => Condition::ElderMap(tokens.boolean()?),
+            "ShapedMap" => Condition::ShapedMap(tokens.boolean()?),
+            "BlightedMap" => Condition::BlightedMap(tokens.boolean()?),
+            "UberBlightedMap" => Condition::UberBlightedMap(tokens.boolean()?),
+            "MapTier" => Condition::MapTier((tokens.operator(), tokens.value()?)),
+            _ => return Err(ParseError::new(format!("unknown condition `{}`", keyword))),
+        };
+        tokens.finish()?;
+        Ok(condition)
+    }
+}
+
+/// Quotes each value and separates them with spaces.
+fn quoted(values: &[String]) -> String {
+    values
+        .iter()
+        .map(|value| format!("\"{}\"", value))
+        .join(" ")
+}
+
+/// An operator followed by a space, or nothing if there is none.
+fn prefix(operator: &Option<Operator>) -> String {
+    match operator {
+        Some(operator) => format!("{} ", operator),
+        None => String::new(),
+    }
+}
+
+/// An operator and count followed by a space, like `>=2 `, or nothing if there is neither.
+fn counted(operator: &Option<Operator>, count: &Option<u8>) -> String {
+    match (operator, count) {
+        (Some(operator), Some(count)) => format!("{}{} ", operator, count),
+        (Some(operator), None) => format!("{} ", operator),
+        (None, Some(count)) => format!("{} ", count),
+        (None, None) => String::new(),
+    }
+}
+
+/// Reads the optional operator and count before a list of mods, written apart like `>= 2` or
+/// together like `>=2`.
+fn count(tokens: &mut Tokens) -> Result<(Option<Operator>, Option<u8>), ParseError> {
+    if let Some(operator) = tokens.optional_operator() {
+        let count = match tokens.peek() {
+            Some(token) if is_count(token) => Some(tokens.value()?),
+            _ => None,
+        };
+        return Ok((Some(operator), count));
+    }
+    let glued = tokens.peek().and_then(|token| {
+        let split = token.find(|c: char| c.is_ascii_digit())?;
+        let (operator, count) = token.split_at(split);
+        let operator = match operator {
+            "" => None,
+            operator => Some(operator.parse::<Operator>().ok()?),
+        };
+        is_count(count).then(|| (operator, count.parse::<u8>().ok()))
+    });
+    match glued {
+        Some((operator, Some(count))) => {
+            tokens.string()?;
+            Ok((operator, Some(count)))
+        }
+        _ => Ok((None, None)),
+    }
+}
+
+/// Whether a token is a count of mods rather than a mod name.
+fn is_count(token: &str) -> bool {
+    !token.is_empty() && token.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Writes a socket count and colors like `5GGG`, leaving out a count of 0.
+fn socket_value(count: u8, colors: &str) -> String {
+    match count {
+        0 => colors.to_string(),
+        count => format!("{}{}", count, colors),
+    }
+}
+
+/// Splits a socket value like `5GGG` into its count and colors. A value with only colors,
+/// like `RGB`, has a count of 0.
+fn sockets(value: &str) -> Result<(u8, String), ParseError> {
+    if value.is_empty() {
+        return Err(ParseError::new("missing sockets"));
+    }
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (count, colors) = value.split_at(split);
+    let count = match count {
+        "" => 0,
+        count => count
+            .parse()
+            .map_err(|_| ParseError::new(format!("invalid sockets `{}`", value)))?,
+    };
+    Ok((count, colors.to_string()))
+}
@@ -1,6 +1,8 @@
 //! The quality of a gem.
+use crate::parse::ParseError;
 use std::fmt;
-#[derive(Debug)]
+use std::str::FromStr;
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 /// The quality of a gem.
 pub enum GemQuality {
     /// The gem is superior
@@ -54,3 +56,17 @@ impl fmt::Display for GemQuality {
         }
     }
 }
+
+impl FromStr for GemQuality {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Superior" => Ok(GemQuality::Superior),
+            "Divergent" => Ok(GemQuality::Divergent),
+            "Anomalous" => Ok(GemQuality::Anomalous),
+            "Phantasmal" => Ok(GemQuality::Phantasmal),
+            _ => Err(ParseError::new(format!("unknown gem quality `{}`", s))),
+        }
+    }
+}
@@ -0,0 +1,55 @@
+//! Import another filter file
+use crate::parse::{ParseError, Tokens};
+use std::fmt;
+use std::str::FromStr;
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// Imports the blocks of another filter file at this point in the filter.
+///
+/// The path is relative to the file containing the import.
+///
+/// # Example
+/// ```
+/// # use libfilter::line::import::Import;
+/// let import = Import { path: String::from("base.filter"), optional: true };
+/// # assert_eq!(import.to_string(), "Import \"base.filter\" Optional");
+/// # assert_eq!("Import \"base.filter\" Optional".parse::<Import>().unwrap(), import);
+/// ```
+pub struct Import {
+    /// The path to the imported filter.
+    pub path: String,
+
+    /// Whether a missing file is ignored instead of being an error.
+    pub optional: bool,
+}
+
+impl fmt::Display for Import {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Import \"{}\"", self.path)?;
+        if self.optional {
+            write!(f, " Optional")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Import {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tokens = Tokens::new(s)?;
+        let keyword = tokens.string()?;
+        if keyword != "Import" {
+            return Err(ParseError::new(format!(
+                "expected `Import`, found `{}`",
+                keyword
+            )));
+        }
+        let path = tokens.string()?;
+        let optional = tokens.peek() == Some("Optional");
+        if optional {
+            tokens.string()?;
+        }
+        tokens.finish()?;
+        Ok(Import { path, optional })
+    }
+}
@@ -1,6 +1,8 @@
 //! Influence
+use crate::parse::ParseError;
 use std::fmt;
-#[derive(Debug)]
+use std::str::FromStr;
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 /// Influence
 pub enum Influence {
     /// Shaper
@@ -87,3 +89,20 @@ impl fmt::Display for Influence {
         }
     }
 }
+
+impl FromStr for Influence {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Shaper" => Ok(Influence::Shaper),
+            "Elder" => Ok(Influence::Elder),
+            "Crusader" => Ok(Influence::Crusader),
+            "Hunter" => Ok(Influence::Hunter),
+            "Redeemer" => Ok(Influence::Redeemer),
+            "Warlord" => Ok(Influence::Warlord),
+            "None" => Ok(Influence::None),
+            _ => Err(ParseError::new(format!("unknown influence `{}`", s))),
+        }
+    }
+}
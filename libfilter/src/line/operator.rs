@@ -1,7 +1,9 @@
 //! Operator
+use crate::parse::ParseError;
 use std::fmt;
+use std::str::FromStr;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 /// Operator
 pub enum Operator {
     /// >
@@ -77,3 +79,19 @@ impl fmt::Display for Operator {
         }
     }
 }
+
+impl FromStr for Operator {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            ">" => Ok(Operator::GreaterThan),
+            ">=" => Ok(Operator::GreaterThanOrEqual),
+            "<" => Ok(Operator::LessThan),
+            "<=" => Ok(Operator::LessThanOrEqual),
+            "==" | "=" => Ok(Operator::Equal),
+            "!=" => Ok(Operator::NotEqual),
+            _ => Err(ParseError::new(format!("unknown operator `{}`", s))),
+        }
+    }
+}
@@ -1,6 +1,8 @@
 //! Item rarity
+use crate::parse::ParseError;
 use std::fmt;
-#[derive(Debug)]
+use std::str::FromStr;
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 /// The rarity of an item
 pub enum Rarity {
     /// Normal
@@ -54,3 +56,17 @@ impl fmt::Display for Rarity {
         }
     }
 }
+
+impl FromStr for Rarity {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Normal" => Ok(Rarity::Normal),
+            "Magic" => Ok(Rarity::Magic),
+            "Rare" => Ok(Rarity::Rare),
+            "Unique" => Ok(Rarity::Unique),
+            _ => Err(ParseError::new(format!("unknown rarity `{}`", s))),
+        }
+    }
+}
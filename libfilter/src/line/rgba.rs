@@ -1,6 +1,6 @@
 //! RGBA
 use std::fmt;
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 /// Defines colors with an alpha channel.
 ///
 /// # Examples
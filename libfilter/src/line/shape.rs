@@ -1,6 +1,8 @@
 //! Predefined shapes for the minimap icon
+use crate::parse::ParseError;
 use std::fmt;
-#[derive(Debug)]
+use std::str::FromStr;
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 /// Predefined shapes for the minimap icon
 pub enum Shape {
     /// Circle
@@ -142,3 +144,25 @@ impl fmt::Display for Shape {
         }
     }
 }
+
+impl FromStr for Shape {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Circle" => Ok(Shape::Circle),
+            "Diamond" => Ok(Shape::Diamond),
+            "Hexagon" => Ok(Shape::Hexagon),
+            "Square" => Ok(Shape::Square),
+            "Star" => Ok(Shape::Star),
+            "Triangle" => Ok(Shape::Triangle),
+            "Cross" => Ok(Shape::Cross),
+            "Moon" => Ok(Shape::Moon),
+            "Raindrop" => Ok(Shape::Raindrop),
+            "Kite" => Ok(Shape::Kite),
+            "Pentagon" => Ok(Shape::Pentagon),
+            "UpsideDownHouse" => Ok(Shape::UpsideDownHouse),
+            _ => Err(ParseError::new(format!("unknown shape `{}`", s))),
+        }
+    }
+}
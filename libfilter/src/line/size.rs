@@ -1,6 +1,8 @@
 //! Size of the minimap icon
+use crate::parse::ParseError;
 use std::fmt;
-#[derive(Debug)]
+use std::str::FromStr;
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 /// The size of the minimap icon
 pub enum Size {
     /// Disable the minimap icon
@@ -54,3 +56,26 @@ impl fmt::Display for Size {
         }
     }
 }
+
+impl FromStr for Size {
+    type Err = ParseError;
+
+    /// Reads a size by name or by the number the game uses, from `0` for the largest icon to
+    /// `2` for the smallest, or `-1` to disable it.
+    ///
+    /// # Example
+    /// ```
+    /// # use libfilter::Size;
+    /// # assert_eq!("0".parse::<Size>().unwrap(), Size::Largest);
+    /// # assert_eq!("-1".parse::<Size>().unwrap(), Size::Disable);
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Disable" | "-1" => Ok(Size::Disable),
+            "Small" | "2" => Ok(Size::Small),
+            "Medium" | "1" => Ok(Size::Medium),
+            "Largest" | "0" => Ok(Size::Largest),
+            _ => Err(ParseError::new(format!("unknown size `{}`", s))),
+        }
+    }
+}
@@ -0,0 +1,250 @@
+//! Reading filter text back into a [crate::filter::Filter].
+use crate::block::Block;
+use crate::line::import::Import;
+use crate::line::operator::Operator;
+use crate::line::Line;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// An error found while parsing filter text.
+///
+/// # Example
+/// ```
+/// # use libfilter::line::condition::Condition;
+/// let error = "AreaLevel > lots".parse::<Condition>().unwrap_err();
+/// # assert_eq!(error.to_string(), "invalid value `lots`: invalid digit found in string");
+/// ```
+pub struct ParseError {
+    /// The 1-based line the error was found on, or 0 if the error is not tied to a line.
+    pub line: usize,
+
+    /// What went wrong.
+    pub message: String,
+}
+
+impl ParseError {
+    /// Creates an error that is not tied to a line.
+    pub fn new(message: impl Into<String>) -> ParseError {
+        ParseError {
+            line: 0,
+            message: message.into(),
+        }
+    }
+
+    /// Ties the error to a line.
+    pub fn at_line(mut self, line: usize) -> ParseError {
+        self.line = line;
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.line > 0 {
+            write!(f, "line {}: {}", self.line, self.message)
+        } else {
+            write!(f, "{}", self.message)
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// The whitespace separated words of a single line, with quotes removed.
+pub(crate) struct Tokens {
+    tokens: Vec<String>,
+    position: usize,
+}
+
+impl Tokens {
+    /// Splits a line into tokens. Anything after a `#` outside of quotes is a comment.
+    pub(crate) fn new(line: &str) -> Result<Tokens, ParseError> {
+        let mut tokens = vec![];
+        let mut chars = line.chars().peekable();
+        while let Some(&c) = chars.peek() {
+            if c.is_whitespace() {
+                chars.next();
+            } else if c == '#' {
+                break;
+            } else if c == '"' {
+                chars.next();
+                let mut token = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some(c) => token.push(c),
+                        None => return Err(ParseError::new("unterminated quote")),
+                    }
+                }
+                tokens.push(token);
+            } else {
+                let mut token = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '"' || c == '#' {
+                        break;
+                    }
+                    token.push(c);
+                    chars.next();
+                }
+                tokens.push(token);
+            }
+        }
+        Ok(Tokens {
+            tokens,
+            position: 0,
+        })
+    }
+
+    /// Whether every token has been consumed.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.position >= self.tokens.len()
+    }
+
+    /// The next token without consuming it.
+    pub(crate) fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.position).map(String::as_str)
+    }
+
+    /// The next token.
+    pub(crate) fn string(&mut self) -> Result<String, ParseError> {
+        let token = self
+            .tokens
+            .get(self.position)
+            .cloned()
+            .ok_or_else(|| ParseError::new("missing value"))?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    /// Every remaining token. At least one is required.
+    pub(crate) fn strings(&mut self) -> Result<Vec<String>, ParseError> {
+        if self.is_empty() {
+            return Err(ParseError::new("missing value"));
+        }
+        let rest = self.tokens[self.position..].to_vec();
+        self.position = self.tokens.len();
+        Ok(rest)
+    }
+
+    /// The next token parsed as a value.
+    pub(crate) fn value<T>(&mut self) -> Result<T, ParseError>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        let token = self.string()?;
+        token
+            .parse()
+            .map_err(|e| ParseError::new(format!("invalid value `{}`: {}", token, e)))
+    }
+
+    /// Every remaining token parsed as a value. At least one is required.
+    pub(crate) fn values<T>(&mut self) -> Result<Vec<T>, ParseError>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        let mut values = vec![self.value()?];
+        while !self.is_empty() {
+            values.push(self.value()?);
+        }
+        Ok(values)
+    }
+
+    /// The next token parsed as a boolean. The game accepts any case.
+    pub(crate) fn boolean(&mut self) -> Result<bool, ParseError> {
+        let token = self.string()?;
+        match token.to_lowercase().as_str() {
+            "true" => Ok(true),
+            "false" => Ok(false),
+            _ => Err(ParseError::new(format!("invalid boolean `{}`", token))),
+        }
+    }
+
+    /// The next token if it is an [Operator], otherwise [Operator::Equal].
+    pub(crate) fn operator(&mut self) -> Operator {
+        self.optional_operator().unwrap_or(Operator::Equal)
+    }
+
+    /// The next token if it is an [Operator], otherwise `None`.
+    pub(crate) fn optional_operator(&mut self) -> Option<Operator> {
+        let operator = self.peek().and_then(|token| token.parse().ok());
+        if operator.is_some() {
+            self.position += 1;
+        }
+        operator
+    }
+
+    /// Fails if any tokens are left over.
+    pub(crate) fn finish(self) -> Result<(), ParseError> {
+        match self.peek() {
+            Some(token) => Err(ParseError::new(format!("unexpected `{}`", token))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A top level statement in a filter file.
+pub(crate) enum Item {
+    /// A `###` line. The text after the hashes.
+    ///
+    /// Lines of four or more hashes, such as `#######` separators, are comments.
+    Heading(String),
+
+    /// A `Show` or `Hide` block.
+    Block(Block),
+
+    /// An `Import` directive.
+    Import(Import),
+}
+
+/// Splits filter text into its top level statements.
+pub(crate) fn items(text: &str) -> Result<Vec<Item>, ParseError> {
+    let mut items = vec![];
+    for (index, raw) in text.lines().enumerate() {
+        let number = index + 1;
+        let line = raw.trim();
+        let heading = line
+            .strip_prefix("###")
+            .filter(|heading| !heading.starts_with('#'));
+        if let Some(heading) = heading {
+            items.push(Item::Heading(heading.to_string()));
+            continue;
+        }
+        let mut tokens = Tokens::new(line).map_err(|e| e.at_line(number))?;
+        match tokens.peek() {
+            None => continue,
+            Some("Show") | Some("Hide") => {
+                let is_hidden = tokens.string()? == "Hide";
+                tokens.finish().map_err(|e| e.at_line(number))?;
+                let comment = line
+                    .split_once('#')
+                    .map(|(_, comment)| comment.trim().to_string())
+                    .filter(|comment| !comment.is_empty());
+                items.push(Item::Block(Block {
+                    lines: vec![],
+                    is_hidden,
+                    comment,
+                }));
+            }
+            Some("Import") => {
+                items.push(Item::Import(
+                    line.parse().map_err(|e: ParseError| e.at_line(number))?,
+                ));
+            }
+            Some(_) => match items.last_mut() {
+                Some(Item::Block(block)) => {
+                    block
+                        .lines
+                        .push(line.parse::<Line>().map_err(|e| e.at_line(number))?);
+                }
+                _ => {
+                    return Err(ParseError::new("line outside of a block").at_line(number));
+                }
+            },
+        }
+    }
+    Ok(items)
+}
@@ -0,0 +1,190 @@
+//! Edits re-applied on top of an upstream filter.
+use crate::block::Block;
+use crate::filter::Filter;
+use crate::line::action::Action;
+use crate::selector::Selector;
+use serde::{Deserialize, Serialize};
+use std::mem;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// An edit to every block picked out by a [Selector].
+///
+/// Patches serialize with selectors, actions and blocks written as filter text, so a list of
+/// them can be kept in a file and re-applied to each new upstream release.
+///
+/// # Example
+/// ```
+/// # use libfilter::filter::Filter;
+/// # use libfilter::patch::{Edit, Patch};
+/// # use libfilter::selector::Selector;
+/// # use libfilter::line::action::Action;
+/// let mut filter = "###RING\n###Rings\nShow\n\tBaseType \"Two-Stone Ring\"\n\tSetFontSize 30".parse::<Filter>().unwrap();
+/// let patches = vec![
+///     Patch {
+///         selector: Selector::BaseType(String::from("Two-Stone")),
+///         edit: Edit::Restyle(vec![Action::SetFontSize(45)]),
+///     },
+///     Patch {
+///         selector: Selector::Section(String::from("GONE")),
+///         edit: Edit::Remove,
+///     },
+/// ];
+/// let report = filter.apply_patches(&patches);
+/// # assert_eq!(filter.sections[0].blocks[0].to_string(), "Show\n\tBaseType \"Two-Stone Ring\"\n\tSetFontSize 45");
+/// # let unmatched = report.unmatched().map(|selector| selector.to_string()).collect::<Vec<_>>();
+/// # assert_eq!(unmatched, ["section:GONE"]);
+/// let saved = serde_json::to_string(&patches).unwrap();
+/// # assert_eq!(saved, r#"[{"selector":"basetype:Two-Stone","edit":{"Restyle":["SetFontSize 45"]}},{"selector":"section:GONE","edit":"Remove"}]"#);
+/// # assert_eq!(serde_json::from_str::<Vec<Patch>>(&saved).unwrap(), patches);
+/// ```
+pub struct Patch {
+    /// Which blocks to edit.
+    #[serde(with = "text")]
+    pub selector: Selector,
+
+    /// What to do to them.
+    pub edit: Edit,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// What a [Patch] does to a block.
+pub enum Edit {
+    /// Sets each action, replacing any action of the same kind.
+    Restyle(#[serde(with = "texts")] Vec<Action>),
+
+    /// Hides the block.
+    Hide,
+
+    /// Shows the block.
+    Show,
+
+    /// Inserts a block before the block.
+    InsertBefore(#[serde(with = "text")] Block),
+
+    /// Inserts a block after the block.
+    InsertAfter(#[serde(with = "text")] Block),
+
+    /// Removes the block.
+    Remove,
+}
+
+impl Edit {
+    /// Applies the edit, returning the blocks that take the place of `block`.
+    fn apply(&self, mut block: Block) -> Vec<Block> {
+        match self {
+            Edit::Restyle(actions) => {
+                for action in actions {
+                    block.set_action(action.clone());
+                }
+                vec![block]
+            }
+            Edit::Hide => {
+                block.is_hidden = true;
+                vec![block]
+            }
+            Edit::Show => {
+                block.is_hidden = false;
+                vec![block]
+            }
+            Edit::InsertBefore(inserted) => vec![inserted.clone(), block],
+            Edit::InsertAfter(inserted) => vec![block, inserted.clone()],
+            Edit::Remove => vec![],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+/// How many blocks each patch matched.
+pub struct PatchReport {
+    /// The selector of each patch and the number of blocks it matched, in the order the
+    /// patches were given.
+    pub matches: Vec<(Selector, usize)>,
+}
+
+impl PatchReport {
+    /// The selectors of the patches that no longer match any block.
+    pub fn unmatched(&self) -> impl Iterator<Item = &Selector> {
+        self.matches
+            .iter()
+            .filter(|(_, count)| *count == 0)
+            .map(|(selector, _)| selector)
+    }
+}
+
+impl Filter {
+    /// Applies each patch in order to every block it selects.
+    ///
+    /// Blocks inserted by a patch are not matched by that same patch.
+    pub fn apply_patches(&mut self, patches: &[Patch]) -> PatchReport {
+        let mut report = PatchReport::default();
+        for patch in patches {
+            let mut count = 0;
+            for section in &mut self.sections {
+                let blocks = mem::take(&mut section.blocks);
+                for block in blocks {
+                    if patch.selector.matches(&section.header, &block) {
+                        count += 1;
+                        section.blocks.extend(patch.edit.apply(block));
+                    } else {
+                        section.blocks.push(block);
+                    }
+                }
+            }
+            report.matches.push((patch.selector.clone(), count));
+        }
+        report
+    }
+}
+
+/// Serializes a value as its filter text.
+mod text {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::fmt::Display;
+    use std::str::FromStr;
+
+    pub fn serialize<T: Display, S: Serializer>(
+        value: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(value)
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: FromStr,
+        T::Err: Display,
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(D::Error::custom)
+    }
+}
+
+/// Serializes a list of values as their filter text.
+mod texts {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::fmt::Display;
+    use std::str::FromStr;
+
+    pub fn serialize<T: Display, S: Serializer>(
+        values: &[T],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(values.iter().map(ToString::to_string))
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Vec<T>, D::Error>
+    where
+        T: FromStr,
+        T::Err: Display,
+        D: Deserializer<'de>,
+    {
+        Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .map(|value| value.parse().map_err(D::Error::custom))
+            .collect()
+    }
+}
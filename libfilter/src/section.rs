@@ -1,5 +1,6 @@
 //! A collection of blocks.
 use crate::block::Block;
+use crate::line::import::Import;
 use itertools::Itertools;
 use std::fmt;
 /// A collection of blocks.
@@ -23,10 +24,14 @@ use std::fmt;
 ///                 Line::Condition(Condition::Identified(true)),
 ///                 Line::Action(Action::SetBorderColor(RGBA{r: 255, g: 0, b: 0, a: 255})),
 ///                 ],
-///             is_hidden: false
-///                 },],};
+///             is_hidden: false,
+///             comment: None,
+///                 },],
+///     imports: vec![],
+/// };
 /// # assert_eq!(section.to_string(), "###FFFF\n###Customize what players see when an item drops in the game.\nShow\n\tAreaLevel > 1\n\tIdentified true\n\tSetBorderColor 255 0 0 255");
 /// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Section {
     /// Four digit code that can be used to jump around in the filter.
     pub header: String,
@@ -36,18 +41,32 @@ pub struct Section {
 
     /// The description of the section.
     pub description: String,
+
+    /// Other filters imported after the blocks, so the blocks take precedence over them.
+    pub imports: Vec<Import>,
+}
+
+impl Section {
+    /// Whether the section has header lines, which sections before the first heading lack.
+    pub fn is_titled(&self) -> bool {
+        !self.header.is_empty() || !self.description.is_empty()
+    }
 }
 
 impl fmt::Display for Section {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let mut section_string = String::new();
-        section_string.push_str("###");
-        section_string.push_str(&self.header);
-        section_string.push_str("\n");
-        section_string.push_str("###");
-        section_string.push_str(&self.description);
-        section_string.push_str("\n");
-        section_string.push_str(&self.blocks.iter().join("\n"));
+        if self.is_titled() {
+            section_string.push_str("###");
+            section_string.push_str(&self.header);
+            section_string.push('\n');
+            section_string.push_str("###");
+            section_string.push_str(&self.description);
+            section_string.push('\n');
+        }
+        let blocks = self.blocks.iter().map(|block| block.to_string());
+        let imports = self.imports.iter().map(|import| import.to_string());
+        section_string.push_str(&blocks.chain(imports).join("\n"));
         write!(f, "{}", section_string)
     }
 }
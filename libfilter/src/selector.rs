@@ -0,0 +1,227 @@
+//! Picks out blocks in a filter.
+use crate::block::Block;
+use crate::filter::Filter;
+use crate::line::action::Action;
+use crate::line::condition::{self, Condition};
+use crate::line::rgba::RGBA;
+use crate::line::Line;
+use crate::parse::{ParseError, Tokens};
+use crate::section::Section;
+use itertools::Itertools;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Picks out blocks in a filter.
+///
+/// Selectors can also be written as text, one term per criterion, all of which must match:
+///
+/// | Term | Matches blocks |
+/// | --- | --- |
+/// | `section:FFFF` | in the section with header `FFFF` |
+/// | `hidden` or `shown` | that are hidden or shown |
+/// | `has:ItemLevel` | with an `ItemLevel` condition |
+/// | `basetype:"Two-Stone"` | with a `BaseType` containing `Two-Stone` |
+/// | `color:255,0,0` | with a text, border or background color of `255 0 0 255` |
+/// | `tag:$tier->t1` | whose comment has the tag `$tier->t1` |
+///
+/// # Example
+/// ```
+/// # use libfilter::selector::Selector;
+/// # use libfilter::filter::Filter;
+/// let filter = "###RING\n###Rings\nShow\n\tBaseType \"Two-Stone Ring\"\nHide\n\tBaseType \"Iron Ring\"".parse::<Filter>().unwrap();
+/// let selector = Selector::All(vec![
+///     Selector::Section(String::from("RING")),
+///     Selector::BaseType(String::from("Stone")),
+/// ]);
+/// # assert_eq!("section:RING basetype:Stone".parse::<Selector>().unwrap(), selector);
+/// let section = &filter.sections[0];
+/// # assert!(selector.matches(&section.header, &section.blocks[0]));
+/// # assert!(!selector.matches(&section.header, &section.blocks[1]));
+/// ```
+///
+/// Conditions are checked against the keywords filters use, so a typo is an error:
+/// ```
+/// # use libfilter::selector::Selector;
+/// let error = "has:BaseTyp".parse::<Selector>().unwrap_err();
+/// # assert_eq!(error.to_string(), "unknown condition `BaseTyp`");
+/// ```
+pub enum Selector {
+    /// Blocks in the section with this header.
+    Section(String),
+
+    /// Blocks that are hidden, or shown when `false`.
+    Hidden(bool),
+
+    /// Blocks with a condition that starts with this keyword, such as `ItemLevel`.
+    Condition(String),
+
+    /// Blocks with a `BaseType` condition containing this text.
+    BaseType(String),
+
+    /// Blocks with a text, border or background color of this value.
+    Color(RGBA),
+
+    /// Blocks whose comment has this tag, such as `$type->currency`.
+    Tag(String),
+
+    /// Blocks matched by every selector.
+    All(Vec<Selector>),
+}
+
+impl Selector {
+    /// Whether the block, which belongs to the section with `header`, is picked out.
+    pub fn matches(&self, header: &str, block: &Block) -> bool {
+        match self {
+            Selector::Section(text) => header == text,
+            Selector::Hidden(is_hidden) => block.is_hidden == *is_hidden,
+            Selector::Condition(keyword) => block.lines.iter().any(|line| match line {
+                Line::Condition(condition) => condition.keyword() == keyword,
+                _ => false,
+            }),
+            Selector::BaseType(text) => block.lines.iter().any(|line| match line {
+                Line::Condition(Condition::BaseType((_, values))) => {
+                    values.iter().any(|value| value.contains(text.as_str()))
+                }
+                _ => false,
+            }),
+            Selector::Color(color) => block.lines.iter().any(|line| match line {
+                Line::Action(Action::SetTextColor(value))
+                | Line::Action(Action::SetBorderColor(value))
+                | Line::Action(Action::SetBackgroundColor(value)) => value == color,
+                _ => false,
+            }),
+            Selector::Tag(tag) => block.tags().any(|value| value == tag),
+            Selector::All(selectors) => selectors
+                .iter()
+                .all(|selector| selector.matches(header, block)),
+        }
+    }
+}
+
+impl Filter {
+    /// Every block picked out by the selector, in order.
+    ///
+    /// # Example
+    /// ```
+    /// # use libfilter::filter::Filter;
+    /// let filter = "Show # $tier->t1\n\tItemLevel >= 86\nHide\n\tItemLevel < 86".parse::<Filter>().unwrap();
+    /// let blocks = filter.query(&"tag:$tier->t1 has:ItemLevel".parse().unwrap());
+    /// # assert_eq!(blocks.len(), 1);
+    /// # assert_eq!(blocks[0].to_string(), "Show # $tier->t1\n\tItemLevel >= 86");
+    /// ```
+    pub fn query(&self, selector: &Selector) -> Vec<&Block> {
+        self.sections
+            .iter()
+            .flat_map(|section| {
+                section
+                    .blocks
+                    .iter()
+                    .filter(|block| selector.matches(&section.header, block))
+            })
+            .collect()
+    }
+
+    /// Every block picked out by the selector, in order, for editing.
+    ///
+    /// # Example
+    /// ```
+    /// # use libfilter::filter::Filter;
+    /// let mut filter = "Show\n\tSetTextColor 255 0 0 255\nShow\n\tSetTextColor 0 0 255 255".parse::<Filter>().unwrap();
+    /// for block in filter.query_mut(&"color:255,0,0".parse().unwrap()) {
+    ///     block.is_hidden = true;
+    /// }
+    /// # assert_eq!(filter.to_string(), "Hide\n\tSetTextColor 255 0 0 255\nShow\n\tSetTextColor 0 0 255 255\n");
+    /// ```
+    pub fn query_mut(&mut self, selector: &Selector) -> Vec<&mut Block> {
+        self.sections
+            .iter_mut()
+            .flat_map(|section| {
+                let Section { header, blocks, .. } = section;
+                blocks
+                    .iter_mut()
+                    .filter(|block| selector.matches(header, block))
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for Selector {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Selector::Section(header) => write!(f, "section:{}", quoted(header)),
+            Selector::Hidden(true) => write!(f, "hidden"),
+            Selector::Hidden(false) => write!(f, "shown"),
+            Selector::Condition(keyword) => write!(f, "has:{}", keyword),
+            Selector::BaseType(text) => write!(f, "basetype:{}", quoted(text)),
+            Selector::Color(color) => {
+                write!(f, "color:{},{},{},{}", color.r, color.g, color.b, color.a)
+            }
+            Selector::Tag(tag) => write!(f, "tag:{}", tag),
+            Selector::All(selectors) => write!(f, "{}", selectors.iter().join(" ")),
+        }
+    }
+}
+
+impl FromStr for Selector {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tokens = Tokens::new(s)?;
+        let mut selectors = vec![];
+        while !tokens.is_empty() {
+            let term = tokens.string()?;
+            let (name, value) = match term.split_once(':') {
+                Some((name, "")) => (name.to_string(), Some(tokens.string()?)),
+                Some((name, value)) => (name.to_string(), Some(value.to_string())),
+                None => (term, None),
+            };
+            let selector = match (name.as_str(), value) {
+                ("hidden", None) => Selector::Hidden(true),
+                ("shown", None) => Selector::Hidden(false),
+                ("section", Some(header)) => Selector::Section(header),
+                ("has", Some(keyword)) if condition::KEYWORDS.contains(&keyword.as_str()) => {
+                    Selector::Condition(keyword)
+                }
+                ("has", Some(keyword)) => {
+                    return Err(ParseError::new(format!("unknown condition `{}`", keyword)));
+                }
+                ("basetype", Some(text)) => Selector::BaseType(text),
+                ("color", Some(color)) => Selector::Color(rgba(&color)?),
+                ("tag", Some(tag)) => Selector::Tag(tag),
+                (name, _) => {
+                    return Err(ParseError::new(format!("unknown selector `{}`", name)));
+                }
+            };
+            selectors.push(selector);
+        }
+        match selectors.len() {
+            0 => Err(ParseError::new("empty selector")),
+            1 => Ok(selectors.remove(0)),
+            _ => Ok(Selector::All(selectors)),
+        }
+    }
+}
+
+/// Quotes text containing whitespace.
+fn quoted(text: &str) -> String {
+    if text.contains(char::is_whitespace) {
+        format!("\"{}\"", text)
+    } else {
+        text.to_string()
+    }
+}
+
+/// Reads a comma separated color. Alpha defaults to 255.
+fn rgba(text: &str) -> Result<RGBA, ParseError> {
+    let values = text
+        .split(',')
+        .map(|value| value.trim().parse::<u8>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| ParseError::new(format!("invalid color `{}`: {}", text, e)))?;
+    match values[..] {
+        [r, g, b] => Ok(RGBA { r, g, b, a: 255 }),
+        [r, g, b, a] => Ok(RGBA { r, g, b, a }),
+        _ => Err(ParseError::new(format!("invalid color `{}`", text))),
+    }
+}
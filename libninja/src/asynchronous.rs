@@ -0,0 +1,234 @@
+//! The async client, which every request to poe.ninja goes through.
+//!
+//! Requests are made with an async HTTP client on a Tokio runtime, with time enabled. The
+//! blocking [Client] wraps an [AsyncClient] and its own runtime, so both share rate limiting,
+//! retries and the cache, and take and return the same types. Callers without a runtime can
+//! also run a single future with [block_on].
+use crate::category::Category;
+use crate::client::{self, Client, DEFAULT_BASE_URL};
+use crate::error::Error;
+use crate::http::Http;
+use crate::league::{self, League};
+use crate::price::Price;
+use crate::schema::{Drift, Mode};
+use crate::snapshot::{Snapshot, SnapshotStore};
+use std::future::Future;
+use std::panic;
+use std::sync::Arc;
+use tokio::runtime::{Builder, Runtime};
+
+/// The prices of a category, or why they could not be fetched.
+pub type Fetched = Result<Vec<Price>, Error>;
+
+/// A runtime for one thread with everything requests need.
+pub(crate) fn runtime() -> Runtime {
+    Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("the runtime should start")
+}
+
+/// Runs a future to completion on a new runtime, for callers without one.
+///
+/// Panics if called from within a runtime.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    runtime().block_on(future)
+}
+
+#[derive(Clone)]
+/// Fetches from poe.ninja without blocking the caller.
+///
+/// # Example
+/// ```no_run
+/// # use libninja::asynchronous::{block_on, AsyncClient};
+/// # use libninja::Category;
+/// let client = AsyncClient::new();
+/// let prices = block_on(client.fetch_all("Standard", Category::ALL));
+/// for (category, prices) in prices {
+///     println!("{}: {} prices", category, prices.unwrap().len());
+/// }
+/// ```
+pub struct AsyncClient {
+    pub(crate) http: Arc<Http>,
+    pub(crate) base_url: String,
+    mode: Mode,
+}
+
+impl AsyncClient {
+    /// Creates a client for poe.ninja.
+    pub fn new() -> AsyncClient {
+        AsyncClient::with_base_url(DEFAULT_BASE_URL)
+    }
+
+    /// Creates a client for another server with the same API, such as a mirror.
+    pub fn with_base_url(base_url: &str) -> AsyncClient {
+        AsyncClient {
+            http: Arc::new(Http::new()),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            mode: Mode::Strict,
+        }
+    }
+
+    /// Sets how responses that differ from the known shape are handled.
+    pub fn mode(mut self, mode: Mode) -> AsyncClient {
+        self.mode = mode;
+        self
+    }
+
+    /// Sets how requests are made, such as the user agent and rate limit.
+    pub fn http(mut self, http: Http) -> AsyncClient {
+        self.http = Arc::new(http);
+        self
+    }
+
+    /// Fetches the current prices of a category in a league.
+    pub async fn fetch(&self, league: &str, category: Category) -> Fetched {
+        self.fetch_with_drift(league, category)
+            .await
+            .map(|(prices, _)| prices)
+    }
+
+    /// Fetches the current prices of a category in a league, with how the response differs
+    /// from the known shape.
+    ///
+    /// In strict mode any difference is an error, so the drift is always empty.
+    pub async fn fetch_with_drift(
+        &self,
+        league: &str,
+        category: Category,
+    ) -> Result<(Vec<Price>, Drift), Error> {
+        let endpoint = if category.is_currency() {
+            "currencyoverview"
+        } else {
+            "itemoverview"
+        };
+        let body = self
+            .http
+            .get(
+                &format!("{}/{}", self.base_url, endpoint),
+                &[("league", league), ("type", &category.to_string())],
+            )
+            .await?;
+        client::parse(category, &body, self.mode)
+    }
+
+    /// Fetches several categories at once, returning each category's result in the order given.
+    ///
+    /// The requests still respect the client's rate limit. A panic while fetching is resumed
+    /// here.
+    pub async fn fetch_all(
+        &self,
+        league: &str,
+        categories: impl IntoIterator<Item = Category>,
+    ) -> Vec<(Category, Fetched)> {
+        let tasks = categories
+            .into_iter()
+            .map(|category| {
+                let client = self.clone();
+                let league = league.to_string();
+                let task = tokio::spawn(async move { client.fetch(&league, category).await });
+                (category, task)
+            })
+            .collect::<Vec<_>>();
+        let mut fetched = vec![];
+        for (category, task) in tasks {
+            match task.await {
+                Ok(prices) => fetched.push((category, prices)),
+                Err(e) => panic::resume_unwind(e.into_panic()),
+            }
+        }
+        fetched
+    }
+
+    /// Fetches the leagues with economy data.
+    pub async fn leagues(&self) -> Result<Vec<League>, Error> {
+        let body = self
+            .http
+            .get(&format!("{}/getindexstate", self.base_url), &[])
+            .await?;
+        league::parse(&body)
+    }
+
+    /// The prices of a category from a store, fetching them and saving them as a snapshot if
+    /// there is no fresh one, like [SnapshotStore::get].
+    pub async fn snapshot(
+        &self,
+        store: &SnapshotStore,
+        league: &str,
+        category: Category,
+    ) -> Result<Snapshot, Error> {
+        if let Some(snapshot) = store.stored(league, category)? {
+            return Ok(snapshot);
+        }
+        let (prices, drift) = self.fetch_with_drift(league, category).await?;
+        store.record(league, category, prices, drift)
+    }
+}
+
+impl Default for AsyncClient {
+    fn default() -> AsyncClient {
+        AsyncClient::new()
+    }
+}
+
+impl From<Client> for AsyncClient {
+    fn from(client: Client) -> AsyncClient {
+        client.asynchronous().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::serve;
+    use std::time::Duration;
+
+    const EMPTY: &str =
+        "HTTP/1.1 200 OK\r\nContent-Length: 12\r\nConnection: close\r\n\r\n{\"lines\":[]}";
+
+    #[test]
+    fn fetches_categories_concurrently() {
+        let (url, requests) = serve(vec![EMPTY, EMPTY, EMPTY]);
+        let client = AsyncClient::with_base_url(&url).http(Http::new().rate_limit(Duration::ZERO));
+        let categories = [Category::Currency, Category::Scarab, Category::Fossil];
+        let results = block_on(client.fetch_all("Standard", categories));
+        assert_eq!(
+            results
+                .iter()
+                .map(|(category, _)| *category)
+                .collect::<Vec<_>>(),
+            categories
+        );
+        assert!(results.iter().all(|(_, prices)| prices.is_ok()));
+        assert_eq!(requests.lock().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn reports_errors_through_the_future() {
+        let (url, _) = serve(vec![
+            "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        ]);
+        let client = AsyncClient::with_base_url(&url);
+        assert!(block_on(client.fetch("Standard", Category::Currency)).is_err());
+    }
+
+    #[test]
+    fn keeps_snapshots_like_the_blocking_client() {
+        let (url, requests) = serve(vec![
+            "HTTP/1.1 200 OK\r\nContent-Length: 59\r\nConnection: close\r\n\r\n{\"lines\":[{\"name\":\"Mageblood\",\"chaosValue\":1.0,\"shiny\":1}]}",
+        ]);
+        let client = AsyncClient::with_base_url(&url)
+            .mode(Mode::Lenient)
+            .http(Http::new().rate_limit(Duration::ZERO));
+        let directory = std::env::temp_dir().join("libninja_async_snapshots");
+        let _ = std::fs::remove_dir_all(&directory);
+        let store = SnapshotStore::new(&directory);
+        let snapshot =
+            block_on(client.snapshot(&store, "Standard", Category::UniqueAccessory)).unwrap();
+        assert!(snapshot.drift.unwrap().unknown.contains("shiny"));
+        let again =
+            block_on(client.snapshot(&store, "Standard", Category::UniqueAccessory)).unwrap();
+        assert_eq!(again.timestamp, snapshot.timestamp);
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+}
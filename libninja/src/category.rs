@@ -0,0 +1,115 @@
+//! The groups of items poe.ninja prices separately.
+use crate::error::Error;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+/// The groups of items poe.ninja prices separately.
+///
+/// # Example
+/// ```
+/// # use libninja::category::Category;
+/// let category = Category::DivinationCard;
+/// # assert_eq!(category.to_string(), "DivinationCard");
+/// # assert_eq!("DivinationCard".parse::<Category>().unwrap(), category);
+/// ```
+pub enum Category {
+    /// Currency such as Chaos and Divine Orbs.
+    Currency,
+    /// Map fragments.
+    Fragment,
+    /// Divination cards.
+    DivinationCard,
+    /// Skill and support gems.
+    SkillGem,
+    /// Crafting bases by item level and influence.
+    BaseType,
+    /// Regular maps.
+    Map,
+    /// Blighted maps.
+    BlightedMap,
+    /// Blight-ravaged maps.
+    BlightRavagedMap,
+    /// Unique maps.
+    UniqueMap,
+    /// Unique jewels.
+    UniqueJewel,
+    /// Unique flasks.
+    UniqueFlask,
+    /// Unique weapons.
+    UniqueWeapon,
+    /// Unique armour.
+    UniqueArmour,
+    /// Unique accessories.
+    UniqueAccessory,
+    /// Cluster jewels by enchantment, passive count and item level.
+    ClusterJewel,
+    /// Scarabs.
+    Scarab,
+    /// Fossils.
+    Fossil,
+    /// Resonators.
+    Resonator,
+    /// Essences.
+    Essence,
+    /// Oils.
+    Oil,
+    /// Delirium orbs.
+    DeliriumOrb,
+    /// Incubators.
+    Incubator,
+    /// Maven's invitations and similar.
+    Invitation,
+}
+
+impl Category {
+    /// Every category, in the order poe.ninja lists them.
+    pub const ALL: [Category; 23] = [
+        Category::Currency,
+        Category::Fragment,
+        Category::DivinationCard,
+        Category::SkillGem,
+        Category::BaseType,
+        Category::Map,
+        Category::BlightedMap,
+        Category::BlightRavagedMap,
+        Category::UniqueMap,
+        Category::UniqueJewel,
+        Category::UniqueFlask,
+        Category::UniqueWeapon,
+        Category::UniqueArmour,
+        Category::UniqueAccessory,
+        Category::ClusterJewel,
+        Category::Scarab,
+        Category::Fossil,
+        Category::Resonator,
+        Category::Essence,
+        Category::Oil,
+        Category::DeliriumOrb,
+        Category::Incubator,
+        Category::Invitation,
+    ];
+
+    /// Whether poe.ninja prices the category with the currency endpoint instead of the item one.
+    pub fn is_currency(&self) -> bool {
+        matches!(self, Category::Currency | Category::Fragment)
+    }
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl FromStr for Category {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Category::ALL
+            .into_iter()
+            .find(|category| category.to_string() == s)
+            .ok_or_else(|| Error::UnknownCategory(s.to_string()))
+    }
+}
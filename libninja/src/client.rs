@@ -0,0 +1,294 @@
+//! Fetches prices from poe.ninja.
+use crate::asynchronous::{self, AsyncClient};
+use crate::category::Category;
+use crate::error::Error;
+use crate::http::Http;
+use crate::price::Price;
+use crate::schema::{self, Drift, Mode};
+use serde::Deserialize;
+use serde_json::Value;
+use tokio::runtime::Runtime;
+
+/// The poe.ninja economy API.
+pub const DEFAULT_BASE_URL: &str = "https://poe.ninja/api/data";
+
+/// Fetches prices from poe.ninja, blocking until each request is done.
+///
+/// Requests are made by an [AsyncClient] on the client's own runtime, so a client must not be
+/// used from within another runtime.
+///
+/// # Example
+/// ```no_run
+/// # use libninja::client::Client;
+/// # use libninja::category::Category;
+/// let client = Client::new();
+/// let prices = client.fetch("Standard", Category::Currency).unwrap();
+/// ```
+pub struct Client {
+    client: AsyncClient,
+    runtime: Runtime,
+}
+
+impl Client {
+    /// Creates a client for poe.ninja.
+    pub fn new() -> Client {
+        Client::from(AsyncClient::new())
+    }
+
+    /// Creates a client for another server with the same API, such as a mirror.
+    pub fn with_base_url(base_url: &str) -> Client {
+        Client::from(AsyncClient::with_base_url(base_url))
+    }
+
+    /// The async client the requests are made with.
+    pub fn asynchronous(&self) -> &AsyncClient {
+        &self.client
+    }
+
+    /// Runs a request of the async client to completion.
+    pub(crate) fn block_on<T>(&self, request: impl std::future::Future<Output = T>) -> T {
+        self.runtime.block_on(request)
+    }
+
+    /// Fetches the current prices of a category in a league.
+    pub fn fetch(&self, league: &str, category: Category) -> Result<Vec<Price>, Error> {
+        self.fetch_with_drift(league, category)
+            .map(|(prices, _)| prices)
+    }
+
+    /// Fetches the current prices of a category in a league, with how the response differs
+    /// from the known shape.
+    ///
+    /// In strict mode any difference is an error, so the drift is always empty.
+    pub fn fetch_with_drift(
+        &self,
+        league: &str,
+        category: Category,
+    ) -> Result<(Vec<Price>, Drift), Error> {
+        self.block_on(self.client.fetch_with_drift(league, category))
+    }
+
+    /// Sets how responses that differ from the known shape are handled.
+    pub fn mode(mut self, mode: Mode) -> Client {
+        self.client = self.client.mode(mode);
+        self
+    }
+
+    /// Sets how requests are made, such as the user agent and rate limit.
+    pub fn http(mut self, http: Http) -> Client {
+        self.client = self.client.http(http);
+        self
+    }
+}
+
+impl From<AsyncClient> for Client {
+    fn from(client: AsyncClient) -> Client {
+        Client {
+            client,
+            runtime: asynchronous::runtime(),
+        }
+    }
+}
+
+impl Default for Client {
+    fn default() -> Client {
+        Client::new()
+    }
+}
+
+#[derive(Deserialize)]
+struct Overview<T> {
+    lines: Vec<T>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CurrencyLine {
+    currency_type_name: String,
+    chaos_equivalent: f64,
+    receive: Option<Exchange>,
+}
+
+#[derive(Deserialize)]
+struct Exchange {
+    count: u32,
+    listing_count: Option<u32>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ItemLine {
+    name: String,
+    base_type: Option<String>,
+    chaos_value: f64,
+    variant: Option<String>,
+    level_required: Option<u8>,
+    links: Option<u8>,
+    gem_level: Option<u8>,
+    gem_quality: Option<u8>,
+    #[serde(default)]
+    corrupted: bool,
+    map_tier: Option<u8>,
+    stack_size: Option<u16>,
+    item_type: Option<String>,
+    count: Option<u32>,
+    listing_count: Option<u32>,
+}
+
+/// Prices seen in fewer trades than this are low confidence, as on poe.ninja's site. Prices
+/// without a trade count are not, the same as prices without a listing count are trusted.
+const LOW_CONFIDENCE_COUNT: u32 = 5;
+
+/// Reads the prices out of a poe.ninja overview response, with how the response differs from
+/// the known shape.
+pub(crate) fn parse(
+    category: Category,
+    body: &str,
+    mode: Mode,
+) -> Result<(Vec<Price>, Drift), Error> {
+    let mut overview: Overview<Value> = serde_json::from_str(body)?;
+    let mut drift = schema::check(category, &mut overview.lines, mode);
+    if mode == Mode::Strict && !drift.is_empty() {
+        return Err(Error::Schema(drift));
+    }
+    let mut prices = vec![];
+    for line in overview.lines.into_iter().filter(|line| !line.is_null()) {
+        let name = line
+            .get("name")
+            .or_else(|| line.get("currencyTypeName"))
+            .and_then(Value::as_str)
+            .unwrap_or("unnamed line")
+            .to_string();
+        match price(category, line) {
+            Ok(price) => prices.push(price),
+            Err(e) if mode == Mode::Lenient => {
+                drift.affected.push((name, format!("skipped, {}", e)));
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok((prices, drift))
+}
+
+/// Reads the price out of a line of an overview response.
+fn price(category: Category, line: Value) -> Result<Price, serde_json::Error> {
+    if category.is_currency() {
+        let line: CurrencyLine = serde_json::from_value(line)?;
+        return Ok(Price {
+            name: line.currency_type_name,
+            chaos_value: line.chaos_equivalent,
+            listings: line
+                .receive
+                .as_ref()
+                .and_then(|receive| receive.listing_count),
+            low_confidence: line
+                .receive
+                .is_some_and(|receive| receive.count < LOW_CONFIDENCE_COUNT),
+            ..Price::default()
+        });
+    }
+    let has_item_level = matches!(category, Category::BaseType | Category::ClusterJewel);
+    let line: ItemLine = serde_json::from_value(line)?;
+    Ok(Price {
+        name: line.name,
+        base_type: line.base_type,
+        chaos_value: line.chaos_value,
+        variant: line.variant,
+        item_type: line.item_type,
+        item_level: line.level_required.filter(|_| has_item_level),
+        links: line.links,
+        gem_level: line.gem_level,
+        gem_quality: line.gem_quality,
+        corrupted: line.corrupted,
+        map_tier: line.map_tier,
+        stack_size: line.stack_size,
+        listings: line.listing_count,
+        low_confidence: line.count.is_some_and(|count| count < LOW_CONFIDENCE_COUNT),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::serve;
+    use crate::snapshot::SnapshotStore;
+    use std::time::Duration;
+
+    #[test]
+    fn parses_currency_overview() {
+        let body = r#"{"lines":[{"currencyTypeName":"Divine Orb","chaosEquivalent":210.5,"detailsId":"divine-orb"}],"currencyDetails":[]}"#;
+        let prices = parse(Category::Currency, body, Mode::Strict).unwrap().0;
+        assert_eq!(prices.len(), 1);
+        assert_eq!(prices[0].name, "Divine Orb");
+        assert_eq!(prices[0].chaos_value, 210.5);
+        assert!(!prices[0].low_confidence);
+    }
+
+    #[test]
+    fn parses_confidence() {
+        let body = r#"{"lines":[{"currencyTypeName":"Divine Orb","chaosEquivalent":210.5,"receive":{"count":40,"listing_count":812}}]}"#;
+        let prices = parse(Category::Currency, body, Mode::Strict).unwrap().0;
+        assert_eq!(prices[0].listings, Some(812));
+        assert!(!prices[0].low_confidence);
+
+        let body =
+            r#"{"lines":[{"name":"Mageblood","chaosValue":40000.0,"count":2,"listingCount":3}]}"#;
+        let prices = parse(Category::UniqueAccessory, body, Mode::Strict)
+            .unwrap()
+            .0;
+        assert_eq!(prices[0].listings, Some(3));
+        assert!(prices[0].low_confidence);
+    }
+
+    #[test]
+    fn parses_item_overview() {
+        let body = r#"{"lines":[{"id":1,"name":"Hubris Circlet","baseType":"Hubris Circlet","levelRequired":86,"variant":"Shaper","itemType":"Helmet","chaosValue":95.0,"corrupted":false}]}"#;
+        let prices = parse(Category::BaseType, body, Mode::Strict).unwrap().0;
+        assert_eq!(prices[0].item_level, Some(86));
+        assert_eq!(prices[0].variant.as_deref(), Some("Shaper"));
+        assert_eq!(prices[0].item_type.as_deref(), Some("Helmet"));
+        assert!(!prices[0].low_confidence);
+    }
+
+    #[test]
+    fn rejects_drift_when_strict() {
+        let body = r#"{"lines":[{"name":"Mageblood","chaosPrice":40000.0,"count":"many"}]}"#;
+        match parse(Category::UniqueAccessory, body, Mode::Strict) {
+            Err(Error::Schema(drift)) => {
+                assert!(drift.unknown.contains("chaosPrice"));
+                assert!(drift.missing.contains("chaosValue"));
+                assert!(drift.changed.contains_key("count"));
+            }
+            _ => panic!("drift was not rejected"),
+        }
+    }
+
+    #[test]
+    fn degrades_when_lenient() {
+        let body = r#"{"lines":[{"name":"Mageblood","chaosValue":40000.0,"count":"many","shiny":true},{"name":"Headhunter","chaosValue":null}]}"#;
+        let (prices, drift) = parse(Category::UniqueAccessory, body, Mode::Lenient).unwrap();
+        assert_eq!(prices.len(), 1);
+        assert!(!prices[0].low_confidence);
+        assert!(drift.unknown.contains("shiny"));
+        assert_eq!(drift.affected.len(), 2);
+        assert_eq!(drift.affected[0].0, "Mageblood");
+        assert_eq!(drift.affected[1].0, "Headhunter");
+    }
+
+    #[test]
+    fn keeps_drift_on_snapshots() {
+        let (url, _) = serve(vec![
+            "HTTP/1.1 200 OK\r\nContent-Length: 59\r\nConnection: close\r\n\r\n{\"lines\":[{\"name\":\"Mageblood\",\"chaosValue\":1.0,\"shiny\":1}]}",
+        ]);
+        let client = Client::with_base_url(&url)
+            .mode(Mode::Lenient)
+            .http(Http::new().rate_limit(Duration::ZERO));
+        let directory = std::env::temp_dir().join("libninja_drift_snapshots");
+        let _ = std::fs::remove_dir_all(&directory);
+        let snapshot = SnapshotStore::new(&directory)
+            .get(&client, "Standard", Category::UniqueAccessory)
+            .unwrap();
+        assert_eq!(snapshot.prices.len(), 1);
+        assert!(snapshot.drift.unwrap().unknown.contains("shiny"));
+    }
+}
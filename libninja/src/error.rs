@@ -0,0 +1,84 @@
+//! Errors from fetching and storing economy data.
+use crate::category::Category;
+use crate::schema::Drift;
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+/// Errors from fetching and storing economy data.
+pub enum Error {
+    /// The request to the economy source failed.
+    Http(String),
+
+    /// A snapshot could not be read or written.
+    Io(io::Error),
+
+    /// A response or snapshot is not valid JSON of the expected shape.
+    Json(serde_json::Error),
+
+    /// There is no snapshot for the league and category, and fetching is not allowed.
+    Offline(String, Category),
+
+    /// The pinned snapshot does not exist.
+    MissingSnapshot(String, Category, u64),
+
+    /// The name is not a known [Category].
+    UnknownCategory(String),
+
+    /// A line of a price sheet is invalid.
+    Sheet(usize, String),
+
+    /// A response differs from the known shape.
+    Schema(Drift),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Http(message) => write!(f, "request failed: {}", message),
+            Error::Io(e) => write!(f, "{}", e),
+            Error::Json(e) => write!(f, "invalid JSON: {}", e),
+            Error::Offline(league, category) => {
+                write!(f, "no {} snapshot for {} while offline", category, league)
+            }
+            Error::MissingSnapshot(league, category, timestamp) => write!(
+                f,
+                "no {} snapshot for {} at {}",
+                category, league, timestamp
+            ),
+            Error::UnknownCategory(name) => write!(f, "unknown category `{}`", name),
+            Error::Schema(drift) => write!(f, "unexpected response shape in {}", drift),
+            Error::Sheet(line, message) => {
+                write!(f, "invalid price sheet at line {}: {}", line, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::Json(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Error {
+        Error::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Error {
+        Error::Json(e)
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(e: reqwest::Error) -> Error {
+        Error::Http(e.to_string())
+    }
+}
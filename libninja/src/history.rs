@@ -0,0 +1,251 @@
+//! Prices followed across snapshots, so tiering can react to trends instead of single readings.
+use crate::category::Category;
+use crate::error::Error;
+use crate::price::{Price, PriceKey};
+use crate::snapshot::{Snapshot, SnapshotStore};
+use std::collections::BTreeMap;
+
+/// Milliseconds in a day, the unit trends are measured in.
+const DAY: f64 = 24.0 * 60.0 * 60.0 * 1000.0;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+/// The prices of one item over time, oldest first.
+pub struct Series {
+    /// Each reading as a timestamp in milliseconds and a value in Chaos Orbs.
+    pub points: Vec<(u64, f64)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// A summary of how an item's price is moving.
+pub struct Trend {
+    /// The newest value in Chaos Orbs.
+    pub latest: f64,
+
+    /// The median value in Chaos Orbs.
+    pub median: f64,
+
+    /// The least squares slope in Chaos Orbs per day.
+    pub slope: f64,
+
+    /// The slope as a fraction of the mean value, so `0.1` is rising 10% a day.
+    pub growth: f64,
+
+    /// The standard deviation of the changes between readings, as a fraction of the earlier value.
+    pub volatility: f64,
+
+    /// The number of readings.
+    pub samples: usize,
+}
+
+impl Series {
+    /// Summarizes the series, or `None` if it is empty.
+    ///
+    /// # Example
+    /// ```
+    /// # use libninja::history::Series;
+    /// let day = 24 * 60 * 60 * 1000;
+    /// let series = Series { points: vec![(0, 10.0), (day, 12.0), (2 * day, 14.0)] };
+    /// let trend = series.trend().unwrap();
+    /// # assert_eq!(trend.latest, 14.0);
+    /// # assert!((trend.slope - 2.0).abs() < 1e-9);
+    /// # assert!((trend.growth - 2.0 / 12.0).abs() < 1e-9);
+    /// ```
+    pub fn trend(&self) -> Option<Trend> {
+        let (_, latest) = *self.points.last()?;
+        let count = self.points.len() as f64;
+        let mean_time = self.points.iter().map(|(t, _)| *t as f64).sum::<f64>() / count;
+        let mean_value = self.points.iter().map(|(_, v)| v).sum::<f64>() / count;
+        let (covariance, variance) =
+            self.points
+                .iter()
+                .fold((0.0, 0.0), |(covariance, variance), (t, v)| {
+                    let dt = *t as f64 - mean_time;
+                    (covariance + dt * (v - mean_value), variance + dt * dt)
+                });
+        let slope = if variance > 0.0 {
+            covariance / variance * DAY
+        } else {
+            0.0
+        };
+        let changes = self
+            .points
+            .windows(2)
+            .filter(|pair| pair[0].1 > 0.0)
+            .map(|pair| (pair[1].1 - pair[0].1) / pair[0].1)
+            .collect::<Vec<_>>();
+        let volatility = if changes.is_empty() {
+            0.0
+        } else {
+            let mean = changes.iter().sum::<f64>() / changes.len() as f64;
+            (changes.iter().map(|c| (c - mean).powi(2)).sum::<f64>() / changes.len() as f64).sqrt()
+        };
+        let mut values = self.points.iter().map(|(_, v)| *v).collect::<Vec<_>>();
+        values.sort_by(f64::total_cmp);
+        let middle = values.len() / 2;
+        let median = if values.len() % 2 == 0 {
+            (values[middle - 1] + values[middle]) / 2.0
+        } else {
+            values[middle]
+        };
+        Some(Trend {
+            latest,
+            median,
+            slope,
+            growth: if mean_value > 0.0 {
+                slope / mean_value
+            } else {
+                0.0
+            },
+            volatility,
+            samples: self.points.len(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+/// The price series of every item in a category.
+///
+/// # Example
+/// ```
+/// # use libninja::history::History;
+/// # use libninja::snapshot::Snapshot;
+/// # use libninja::{Category, Price};
+/// let snapshot = |timestamp, chaos_value| Snapshot {
+///     league: String::from("Standard"),
+///     category: Category::Currency,
+///     timestamp,
+///     prices: vec![Price { name: String::from("Divine Orb"), chaos_value, ..Price::default() }],
+///     drift: None,
+/// };
+/// let history = History::from_snapshots(&[snapshot(0, 200.0), snapshot(86_400_000, 220.0)]);
+/// let divine = history.series.values().next().unwrap();
+/// # assert_eq!(divine.points, vec![(0, 200.0), (86_400_000, 220.0)]);
+/// ```
+pub struct History {
+    /// The series of each item.
+    pub series: BTreeMap<PriceKey, Series>,
+}
+
+impl History {
+    /// Follows each item across the snapshots.
+    pub fn from_snapshots(snapshots: &[Snapshot]) -> History {
+        let mut history = History::default();
+        let mut snapshots = snapshots.iter().collect::<Vec<_>>();
+        snapshots.sort_by_key(|snapshot| snapshot.timestamp);
+        for snapshot in snapshots {
+            for price in &snapshot.prices {
+                history
+                    .series
+                    .entry(price.key())
+                    .or_default()
+                    .points
+                    .push((snapshot.timestamp, price.chaos_value));
+            }
+        }
+        history
+    }
+
+    /// The trend of an item, if it has been priced.
+    pub fn trend(&self, key: &PriceKey) -> Option<Trend> {
+        self.series.get(key).and_then(Series::trend)
+    }
+}
+
+impl SnapshotStore {
+    /// The history of a category from every stored snapshot taken at or after `since`, and at
+    /// or before the pin when pinned.
+    pub fn history(&self, league: &str, category: Category, since: u64) -> Result<History, Error> {
+        let snapshots = self
+            .timestamps(league, category)?
+            .into_iter()
+            .filter(|timestamp| *timestamp >= since)
+            .filter(|timestamp| self.pin.is_none_or(|pin| *timestamp <= pin))
+            .map(|timestamp| self.load(league, category, timestamp))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(History::from_snapshots(&snapshots))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Turns a trend into the value an item is tiered by.
+///
+/// # Example
+/// ```
+/// # use libninja::history::{Trend, TrendPolicy};
+/// let policy = TrendPolicy::default();
+/// let rising = Trend { latest: 100.0, median: 80.0, slope: 10.0, growth: 0.12, volatility: 0.05, samples: 6 };
+/// # assert_eq!(policy.value(&rising), 120.0);
+/// let spiking = Trend { latest: 500.0, median: 20.0, slope: 200.0, growth: 2.0, volatility: 3.0, samples: 6 };
+/// # assert_eq!(policy.value(&spiking), 20.0);
+/// ```
+pub struct TrendPolicy {
+    /// How many days ahead to project items that are rising.
+    pub horizon_days: f64,
+
+    /// Items with fewer readings use their median value.
+    pub min_samples: usize,
+
+    /// Items more volatile than this use their median value.
+    pub max_volatility: f64,
+}
+
+impl Default for TrendPolicy {
+    fn default() -> TrendPolicy {
+        TrendPolicy {
+            horizon_days: 2.0,
+            min_samples: 3,
+            max_volatility: 0.5,
+        }
+    }
+}
+
+impl TrendPolicy {
+    /// The value to tier an item by.
+    ///
+    /// Rising items are projected ahead so they are promoted early. Items with thin or volatile
+    /// data use their median, so a spike does not promote them, but never more than their latest.
+    pub fn value(&self, trend: &Trend) -> f64 {
+        if trend.samples < self.min_samples || trend.volatility > self.max_volatility {
+            trend.median.min(trend.latest)
+        } else {
+            trend
+                .latest
+                .max(trend.latest + trend.slope * self.horizon_days)
+        }
+    }
+
+    /// The prices with each value replaced by the value to tier it by, from its trend in the
+    /// history. Items without a history keep their price.
+    ///
+    /// # Example
+    /// ```
+    /// # use libninja::history::{History, TrendPolicy};
+    /// # use libninja::snapshot::Snapshot;
+    /// # use libninja::{Category, Price};
+    /// let divine = |chaos_value| Price { name: String::from("Divine Orb"), chaos_value, ..Price::default() };
+    /// let snapshot = |timestamp, chaos_value| Snapshot {
+    ///     league: String::from("Standard"),
+    ///     category: Category::Currency,
+    ///     timestamp,
+    ///     prices: vec![divine(chaos_value)],
+    ///     drift: None,
+    /// };
+    /// let history = History::from_snapshots(&[snapshot(0, 20.0), snapshot(86_400_000, 500.0)]);
+    /// let prices = [divine(500.0), Price { name: String::from("Chaos Orb"), chaos_value: 1.0, ..Price::default() }];
+    /// let trended = TrendPolicy::default().apply(&prices, &history);
+    /// # assert_eq!(trended[0].chaos_value, 260.0);
+    /// # assert_eq!(trended[1].chaos_value, 1.0);
+    /// ```
+    pub fn apply(&self, prices: &[Price], history: &History) -> Vec<Price> {
+        prices
+            .iter()
+            .map(|price| match history.trend(&price.key()) {
+                Some(trend) => Price {
+                    chaos_value: self.value(&trend),
+                    ..price.clone()
+                },
+                None => price.clone(),
+            })
+            .collect()
+    }
+}
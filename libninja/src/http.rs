@@ -0,0 +1,374 @@
+//! Requests to the economy source, made politely and reliably.
+//!
+//! Requests to the same host are spaced out, throttled and failed requests are retried with
+//! exponential backoff, and responses are cached so unchanged data is revalidated with
+//! `If-None-Match` and `If-Modified-Since` instead of downloaded again. The cache can be kept in
+//! a file, so it is reused across runs.
+//!
+//! Requests are asynchronous and need a Tokio runtime with time enabled, which the blocking
+//! [Client](crate::client::Client) brings along.
+use crate::error::Error;
+use reqwest::{Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// The user agent sent by default.
+pub const DEFAULT_USER_AGENT: &str = concat!("libninja/", env!("CARGO_PKG_VERSION"));
+
+#[derive(Serialize, Deserialize)]
+/// A response kept for revalidation.
+struct Cached {
+    etag: Option<String>,
+    last_modified: Option<String>,
+    body: String,
+}
+
+/// Makes GET requests with rate limiting, retries, conditional requests and timeouts.
+///
+/// # Example
+/// ```
+/// # use libninja::http::Http;
+/// # use std::time::Duration;
+/// let http = Http::new()
+///     .user_agent("rongo (contact@example.com)")
+///     .timeout(Duration::from_secs(10))
+///     .rate_limit(Duration::from_millis(500))
+///     .retries(3);
+/// ```
+pub struct Http {
+    agent: reqwest::Client,
+    user_agent: String,
+    timeout: Duration,
+    rate_limit: Duration,
+    retries: u32,
+    backoff: Duration,
+    max_backoff: Duration,
+    next_request: Mutex<HashMap<String, Instant>>,
+    cache: Mutex<HashMap<String, Cached>>,
+    cache_file: Option<PathBuf>,
+}
+
+impl Http {
+    /// Creates a request layer with a 30 second timeout, at most two requests a second to each
+    /// host, and up to 4 retries starting one second apart.
+    pub fn new() -> Http {
+        let user_agent = DEFAULT_USER_AGENT.to_string();
+        let timeout = Duration::from_secs(30);
+        Http {
+            agent: agent(&user_agent, timeout),
+            user_agent,
+            timeout,
+            rate_limit: Duration::from_millis(500),
+            retries: 4,
+            backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            next_request: Mutex::new(HashMap::new()),
+            cache: Mutex::new(HashMap::new()),
+            cache_file: None,
+        }
+    }
+
+    /// Sets the user agent, which should say who to contact about the requests.
+    pub fn user_agent(mut self, user_agent: &str) -> Http {
+        self.user_agent = user_agent.to_string();
+        self.agent = agent(&self.user_agent, self.timeout);
+        self
+    }
+
+    /// Sets how long a request may take in total.
+    pub fn timeout(mut self, timeout: Duration) -> Http {
+        self.timeout = timeout;
+        self.agent = agent(&self.user_agent, self.timeout);
+        self
+    }
+
+    /// Sets the shortest time between the starts of two requests to the same host.
+    pub fn rate_limit(mut self, interval: Duration) -> Http {
+        self.rate_limit = interval;
+        self
+    }
+
+    /// Sets how many times a throttled or failed request is retried.
+    pub fn retries(mut self, retries: u32) -> Http {
+        self.retries = retries;
+        self
+    }
+
+    /// Sets the wait before the first retry, which doubles with each retry up to `max`.
+    ///
+    /// A `Retry-After` header from the server is used instead when present, up to `max`.
+    pub fn backoff(mut self, first: Duration, max: Duration) -> Http {
+        self.backoff = first;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Keeps cached responses in a file, reading any it already holds, so unchanged data is
+    /// revalidated across runs. A missing or unreadable file starts an empty cache.
+    pub fn cache_file(mut self, path: impl Into<PathBuf>) -> Http {
+        let path = path.into();
+        let cached = fs::read_to_string(&path)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default();
+        self.cache = Mutex::new(cached);
+        self.cache_file = Some(path);
+        self
+    }
+
+    /// Fetches the body of a URL with the given query parameters.
+    pub async fn get(&self, url: &str, query: &[(&str, &str)]) -> Result<String, Error> {
+        let key = cache_key(url, query);
+        let mut attempt = 0;
+        loop {
+            self.wait_turn(url).await;
+            let mut request = self.agent.get(url).query(query);
+            if let Some(cached) = self.cache.lock().unwrap().get(&key) {
+                if let Some(etag) = &cached.etag {
+                    request = request.header("If-None-Match", etag);
+                }
+                if let Some(last_modified) = &cached.last_modified {
+                    request = request.header("If-Modified-Since", last_modified);
+                }
+            }
+            let retry_after = match request.send().await {
+                Ok(response) if response.status() == StatusCode::NOT_MODIFIED => {
+                    if let Some(cached) = self.cache.lock().unwrap().get(&key) {
+                        return Ok(cached.body.clone());
+                    }
+                    return Err(Error::Http(format!("{}: 304 without a cached body", url)));
+                }
+                Ok(response) if response.status().is_success() => {
+                    let etag = header(&response, "ETag");
+                    let last_modified = header(&response, "Last-Modified");
+                    let body = response.text().await?;
+                    if etag.is_some() || last_modified.is_some() {
+                        self.remember(key, etag, last_modified, &body)?;
+                    }
+                    return Ok(body);
+                }
+                Ok(response)
+                    if (response.status() == StatusCode::TOO_MANY_REQUESTS
+                        || response.status().is_server_error())
+                        && attempt < self.retries =>
+                {
+                    header(&response, "Retry-After")
+                        .and_then(|seconds| seconds.trim().parse().ok())
+                        .map(Duration::from_secs)
+                }
+                Ok(response) => {
+                    return Err(Error::Http(format!(
+                        "{}: status code {}",
+                        url,
+                        response.status().as_u16()
+                    )))
+                }
+                Err(_) if attempt < self.retries => None,
+                Err(e) => return Err(e.into()),
+            };
+            let backoff = self.backoff.saturating_mul(2u32.saturating_pow(attempt));
+            tokio::time::sleep(retry_after.unwrap_or(backoff).min(self.max_backoff)).await;
+            attempt += 1;
+        }
+    }
+
+    /// Caches a response for revalidation, writing the cache file if there is one.
+    fn remember(
+        &self,
+        key: String,
+        etag: Option<String>,
+        last_modified: Option<String>,
+        body: &str,
+    ) -> Result<(), Error> {
+        let mut cache = self.cache.lock().unwrap();
+        cache.insert(
+            key,
+            Cached {
+                etag,
+                last_modified,
+                body: body.to_string(),
+            },
+        );
+        if let Some(path) = &self.cache_file {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, serde_json::to_string(&*cache)?)?;
+        }
+        Ok(())
+    }
+
+    /// Waits until a request to the URL's host is allowed, and claims that slot.
+    async fn wait_turn(&self, url: &str) {
+        let host = url
+            .split("://")
+            .nth(1)
+            .unwrap_or(url)
+            .split('/')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        let now = Instant::now();
+        let start = {
+            let mut next_request = self.next_request.lock().unwrap();
+            let start = next_request.get(&host).map_or(now, |next| (*next).max(now));
+            next_request.insert(host, start + self.rate_limit);
+            start
+        };
+        tokio::time::sleep(start - now).await;
+    }
+}
+
+impl Default for Http {
+    fn default() -> Http {
+        Http::new()
+    }
+}
+
+/// An agent sending the user agent, giving up on requests that take longer than the timeout.
+///
+/// Connections are not kept for reuse, since the runtime that opened one may be gone by the
+/// next request.
+fn agent(user_agent: &str, timeout: Duration) -> reqwest::Client {
+    reqwest::Client::builder()
+        .user_agent(user_agent)
+        .timeout(timeout)
+        .http1_title_case_headers()
+        .pool_max_idle_per_host(0)
+        .build()
+        .expect("the TLS backend should initialize")
+}
+
+/// A header of a response, if it is present and readable.
+fn header(response: &Response, name: &str) -> Option<String> {
+    response
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+/// What a cached response is kept under.
+fn cache_key(url: &str, query: &[(&str, &str)]) -> String {
+    query.iter().fold(url.to_string(), |key, (name, value)| {
+        format!("{}&{}={}", key, name, value)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asynchronous::block_on;
+    use crate::mock::serve;
+
+    fn quick() -> Http {
+        Http::new()
+            .rate_limit(Duration::ZERO)
+            .backoff(Duration::from_millis(10), Duration::from_millis(50))
+    }
+
+    const OK: &str = "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok";
+
+    #[test]
+    fn retries_throttled_and_failed_requests() {
+        let (url, requests) = serve(vec![
+            "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 0\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            OK,
+        ]);
+        assert_eq!(block_on(quick().get(&url, &[])).unwrap(), "ok");
+        assert_eq!(requests.lock().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn gives_up_after_retries() {
+        let failure =
+            "HTTP/1.1 500 Internal Server Error\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+        let (url, requests) = serve(vec![failure, failure, failure]);
+        assert!(block_on(quick().retries(1).get(&url, &[])).is_err());
+        assert_eq!(requests.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn does_not_retry_client_errors() {
+        let (url, requests) = serve(vec![
+            "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            OK,
+        ]);
+        assert!(block_on(quick().get(&url, &[])).is_err());
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn revalidates_cached_responses() {
+        let (url, requests) = serve(vec![
+            "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nLast-Modified: Wed, 21 Oct 2015 07:28:00 GMT\r\nContent-Length: 5\r\nConnection: close\r\n\r\nfirst",
+            "HTTP/1.1 304 Not Modified\r\nConnection: close\r\n\r\n",
+        ]);
+        let http = quick();
+        assert_eq!(
+            block_on(http.get(&url, &[("league", "Standard")])).unwrap(),
+            "first"
+        );
+        assert_eq!(
+            block_on(http.get(&url, &[("league", "Standard")])).unwrap(),
+            "first"
+        );
+        let requests = requests.lock().unwrap();
+        assert!(requests[0].starts_with("GET /data?league=Standard "));
+        assert!(!requests[0].contains("If-None-Match"));
+        assert!(requests[1].contains("If-None-Match: \"v1\""));
+        assert!(requests[1].contains("If-Modified-Since: Wed, 21 Oct 2015 07:28:00 GMT"));
+    }
+
+    #[test]
+    fn revalidates_across_runs() {
+        let (url, requests) = serve(vec![
+            "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nContent-Length: 5\r\nConnection: close\r\n\r\nfirst",
+            "HTTP/1.1 304 Not Modified\r\nConnection: close\r\n\r\n",
+        ]);
+        let path = std::env::temp_dir().join("libninja_http_cache.json");
+        let _ = fs::remove_file(&path);
+        assert_eq!(
+            block_on(quick().cache_file(&path).get(&url, &[])).unwrap(),
+            "first"
+        );
+        assert_eq!(
+            block_on(quick().cache_file(&path).get(&url, &[])).unwrap(),
+            "first"
+        );
+        assert!(requests.lock().unwrap()[1].contains("If-None-Match: \"v1\""));
+    }
+
+    #[test]
+    fn sends_user_agent() {
+        let (url, requests) = serve(vec![OK]);
+        block_on(quick().user_agent("rongo-test").get(&url, &[])).unwrap();
+        assert!(requests.lock().unwrap()[0].contains("User-Agent: rongo-test"));
+    }
+
+    #[test]
+    fn spaces_out_requests_to_a_host() {
+        let (url, _) = serve(vec![OK, OK, OK]);
+        let http = quick().rate_limit(Duration::from_millis(100));
+        let start = Instant::now();
+        for _ in 0..3 {
+            block_on(http.get(&url, &[])).unwrap();
+        }
+        assert!(start.elapsed() >= Duration::from_millis(200));
+    }
+
+    #[test]
+    fn times_out() {
+        let (url, _) = serve(vec![""]);
+        let start = Instant::now();
+        let http = quick().retries(0).timeout(Duration::from_millis(200));
+        let result = block_on(http.get(&url, &[]));
+        assert!(result.is_err());
+        assert!(start.elapsed() < Duration::from_secs(2));
+    }
+}
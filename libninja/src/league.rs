@@ -0,0 +1,159 @@
+//! The leagues poe.ninja prices, and picking the one to use.
+use crate::client::Client;
+use crate::error::Error;
+use crate::snapshot::SnapshotStore;
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+/// The file in a snapshot store holding the last fetched leagues.
+const LEAGUES_FILE: &str = "leagues.json";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A league, with what kind of league it is worked out from its name.
+///
+/// # Example
+/// ```
+/// # use libninja::league::League;
+/// # assert!(League::new("Settlers").is_challenge());
+/// let league = League::new("SSF Hardcore Settlers");
+/// # assert!(league.hardcore && league.solo_self_found && !league.permanent);
+/// # assert!(!league.is_challenge());
+/// # assert!(League::new("Hardcore").permanent);
+/// # assert!(League::new("SSF Standard").permanent);
+/// ```
+pub struct League {
+    /// The name the economy API knows the league by.
+    pub name: String,
+
+    /// Whether the league is hardcore.
+    pub hardcore: bool,
+
+    /// Whether the league is solo self-found.
+    pub solo_self_found: bool,
+
+    /// Whether the league is Standard or Hardcore rather than a temporary league.
+    pub permanent: bool,
+}
+
+impl League {
+    /// A league by name.
+    pub fn new(name: impl Into<String>) -> League {
+        let name = name.into();
+        let words = name.split_whitespace().collect::<Vec<_>>();
+        let hardcore = words.iter().any(|word| matches!(*word, "Hardcore" | "HC"));
+        let solo_self_found = words.contains(&"SSF");
+        let rest = words
+            .iter()
+            .filter(|word| !matches!(**word, "Hardcore" | "HC" | "SSF"))
+            .collect::<Vec<_>>();
+        let permanent = rest.is_empty() || rest == [&"Standard"];
+        League {
+            name,
+            hardcore,
+            solo_self_found,
+            permanent,
+        }
+    }
+
+    /// Whether the league is the softcore trade version of a temporary league.
+    pub fn is_challenge(&self) -> bool {
+        !self.hardcore && !self.solo_self_found && !self.permanent
+    }
+}
+
+/// The league to use by default: the first challenge league.
+pub fn default_league(leagues: &[League]) -> Option<&League> {
+    leagues.iter().find(|league| league.is_challenge())
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IndexState {
+    economy_leagues: Vec<LeagueLine>,
+}
+
+#[derive(Deserialize)]
+struct LeagueLine {
+    name: String,
+    #[serde(default)]
+    hardcore: bool,
+}
+
+/// Reads the leagues out of a poe.ninja index state response.
+pub(crate) fn parse(body: &str) -> Result<Vec<League>, Error> {
+    let state: IndexState = serde_json::from_str(body)?;
+    Ok(state
+        .economy_leagues
+        .into_iter()
+        .map(|line| {
+            let mut league = League::new(line.name);
+            league.hardcore |= line.hardcore;
+            league
+        })
+        .collect())
+}
+
+impl Client {
+    /// Fetches the leagues with economy data.
+    pub fn leagues(&self) -> Result<Vec<League>, Error> {
+        self.block_on(self.asynchronous().leagues())
+    }
+}
+
+impl SnapshotStore {
+    /// The leagues with economy data, fetched unless offline and kept for offline use.
+    ///
+    /// If fetching fails, the last fetched leagues are used instead.
+    pub fn leagues(&self, client: &Client) -> Result<Vec<League>, Error> {
+        let path = self.directory.join(LEAGUES_FILE);
+        let fetched = if self.offline {
+            None
+        } else {
+            match client.leagues() {
+                Ok(leagues) => {
+                    fs::create_dir_all(&self.directory)?;
+                    fs::write(&path, serde_json::to_string(&leagues)?)?;
+                    return Ok(leagues);
+                }
+                Err(e) => Some(e),
+            }
+        };
+        match fs::read_to_string(&path) {
+            Ok(text) => Ok(serde_json::from_str(&text)?),
+            Err(e) => Err(fetched.unwrap_or(Error::Io(e))),
+        }
+    }
+
+    /// The name of the default challenge league, or `fallback` if it cannot be found.
+    ///
+    /// # Example
+    /// ```
+    /// # use libninja::client::Client;
+    /// # use libninja::snapshot::SnapshotStore;
+    /// let directory = std::env::temp_dir().join("libninja_default_league");
+    /// # let _ = std::fs::remove_dir_all(&directory);
+    /// let store = SnapshotStore::new(&directory).offline(true);
+    /// # assert_eq!(store.default_league(&Client::new(), "Standard"), "Standard");
+    /// ```
+    pub fn default_league(&self, client: &Client, fallback: &str) -> String {
+        self.leagues(client)
+            .ok()
+            .and_then(|leagues| default_league(&leagues).map(|league| league.name.clone()))
+            .unwrap_or_else(|| fallback.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_index_state() {
+        let body = r#"{"economyLeagues":[{"name":"Hardcore Settlers","url":"settlershc","displayName":"Hardcore Settlers","hardcore":true,"indexed":true},{"name":"Settlers","url":"settlers","displayName":"Settlers","hardcore":false,"indexed":true},{"name":"Standard","url":"standard","displayName":"Standard","hardcore":false,"indexed":true}],"oldEconomyLeagues":[],"snapshotVersions":[]}"#;
+        let leagues = parse(body).unwrap();
+        assert_eq!(leagues.len(), 3);
+        assert!(leagues[0].hardcore);
+        assert!(leagues[2].permanent);
+        assert_eq!(default_league(&leagues).unwrap().name, "Settlers");
+    }
+}
@@ -1,14 +1,23 @@
-pub fn add(left: usize, right: usize) -> usize {
-    left + right
-}
+#![deny(missing_docs)]
+//! A library for fetching Path of Exile economy data from poe.ninja.
 
+pub mod asynchronous;
+pub mod category;
+pub mod client;
+pub mod error;
+pub mod history;
+pub mod http;
+pub mod league;
 #[cfg(test)]
-mod tests {
-    use super::*;
+mod mock;
+pub mod price;
+pub mod schema;
+pub mod snapshot;
+pub mod source;
 
-    #[test]
-    fn it_works() {
-        let result = add(2, 2);
-        assert_eq!(result, 4);
-    }
-}
+// Re-exports
+pub use crate::category::Category;
+pub use crate::error::Error;
+pub use crate::league::League;
+pub use crate::price::{Price, PriceKey};
+pub use crate::source::PriceSource;
@@ -0,0 +1,36 @@
+//! A local HTTP server for tests.
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// Serves the responses in order, one per connection, returning the server's URL and the
+/// head of each request it received.
+pub(crate) fn serve(responses: Vec<&'static str>) -> (String, Arc<Mutex<Vec<String>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/data", listener.local_addr().unwrap());
+    let requests = Arc::new(Mutex::new(vec![]));
+    let received = Arc::clone(&requests);
+    thread::spawn(move || {
+        for (stream, response) in listener.incoming().zip(responses) {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut head = String::new();
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap() == 0 || line == "\r\n" {
+                    break;
+                }
+                head.push_str(&line);
+            }
+            received.lock().unwrap().push(head);
+            if response.is_empty() {
+                thread::sleep(Duration::from_secs(2));
+                continue;
+            }
+            let _ = stream.write_all(response.as_bytes());
+        }
+    });
+    (url, requests)
+}
@@ -0,0 +1,116 @@
+//! The price of a single item.
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+/// The price of a single item, or of one variant of it.
+///
+/// Only the fields that apply to the item's category are set.
+///
+/// # Example
+/// ```
+/// # use libninja::price::Price;
+/// let price = Price {
+///     name: String::from("The Doctor"),
+///     base_type: Some(String::from("The Doctor")),
+///     chaos_value: 1200.0,
+///     stack_size: Some(8),
+///     ..Price::default()
+/// };
+/// # assert_eq!(price.set_value(), Some(9600.0));
+/// ```
+pub struct Price {
+    /// The name of the item. For uniques this is the unique name, for currency the currency name.
+    pub name: String,
+
+    /// The base type of the item, when it differs from or adds to the name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_type: Option<String>,
+
+    /// The value of one item in Chaos Orbs.
+    pub chaos_value: f64,
+
+    /// What distinguishes this line from others with the same name, such as `Shaper` or `6L`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant: Option<String>,
+
+    /// The kind of item, such as `Ring` or `Body Armour`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub item_type: Option<String>,
+
+    /// The item level of bases and cluster jewels.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub item_level: Option<u8>,
+
+    /// The number of linked sockets.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub links: Option<u8>,
+
+    /// The level of a gem.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gem_level: Option<u8>,
+
+    /// The quality of a gem.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gem_quality: Option<u8>,
+
+    /// Whether the item is corrupted.
+    #[serde(default)]
+    pub corrupted: bool,
+
+    /// The tier of a map.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub map_tier: Option<u8>,
+
+    /// The number of divination cards in a full set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stack_size: Option<u16>,
+
+    /// The number of listings the price was taken from, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub listings: Option<u32>,
+
+    /// Whether poe.ninja had too few trades to be confident in the price. Prices without a
+    /// trade count are not low confidence.
+    #[serde(default)]
+    pub low_confidence: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+/// What tells one priced item apart from another, so it can be followed across snapshots.
+pub struct PriceKey {
+    /// The name of the item.
+    pub name: String,
+    /// The variant of the item.
+    pub variant: Option<String>,
+    /// The item level.
+    pub item_level: Option<u8>,
+    /// The number of linked sockets.
+    pub links: Option<u8>,
+    /// The level of a gem.
+    pub gem_level: Option<u8>,
+    /// The quality of a gem.
+    pub gem_quality: Option<u8>,
+    /// Whether the item is corrupted.
+    pub corrupted: bool,
+}
+
+impl Price {
+    /// What tells this item apart from other items priced in the same category.
+    pub fn key(&self) -> PriceKey {
+        PriceKey {
+            name: self.name.clone(),
+            variant: self.variant.clone(),
+            item_level: self.item_level,
+            links: self.links,
+            gem_level: self.gem_level,
+            gem_quality: self.gem_quality,
+            corrupted: self.corrupted,
+        }
+    }
+
+    /// The value of a full set of divination cards.
+    pub fn set_value(&self) -> Option<f64> {
+        self.stack_size
+            .map(|stack_size| self.chaos_value * f64::from(stack_size))
+    }
+}
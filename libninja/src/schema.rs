@@ -0,0 +1,257 @@
+//! Noticing when poe.ninja changes the shape of its responses.
+//!
+//! Every field of every line is checked against the fields poe.ninja is known to send. In
+//! [Mode::Strict] any difference is an error, so a renamed field cannot quietly empty a tier.
+//! In [Mode::Lenient] fields of the wrong type are ignored and lines missing a required field
+//! are skipped, and the [Drift] says which items were affected.
+use crate::category::Category;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+/// The type of a JSON value.
+pub enum Kind {
+    /// `null`.
+    Null,
+    /// `true` or `false`.
+    Bool,
+    /// A number.
+    Number,
+    /// A string.
+    String,
+    /// An array.
+    Array,
+    /// An object.
+    Object,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// How differences from the known response shape are handled.
+pub enum Mode {
+    /// Any difference is an error.
+    #[default]
+    Strict,
+
+    /// Differences are reported, affected fields ignored and unusable lines skipped.
+    Lenient,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+/// How a response differs from the known shape.
+///
+/// # Example
+/// ```
+/// # use libninja::schema::{Drift, Kind};
+/// # use libninja::Category;
+/// let mut drift = Drift::new(Category::Scarab);
+/// drift.unknown.insert(String::from("chaosPrice"));
+/// drift.missing.insert(String::from("chaosValue"));
+/// drift.changed.insert(String::from("count"), (Kind::Number, Kind::String));
+/// # assert_eq!(drift.to_string(), "Scarab: unknown fields chaosPrice; missing fields chaosValue; count is string instead of number");
+/// ```
+pub struct Drift {
+    /// The category of the response.
+    pub category: Option<Category>,
+
+    /// Fields that are not known.
+    pub unknown: BTreeSet<String>,
+
+    /// Required fields missing or `null` on at least one line.
+    pub missing: BTreeSet<String>,
+
+    /// Known fields with another type on at least one line, with the known and the found type.
+    pub changed: BTreeMap<String, (Kind, Kind)>,
+
+    /// The items affected in lenient mode, with what happened to them.
+    pub affected: Vec<(String, String)>,
+}
+
+/// A field poe.ninja is known to send, its type, and whether a line is unusable without it.
+type Field = (&'static str, Kind, bool);
+
+/// The fields of a line of a currency overview.
+const CURRENCY_FIELDS: &[Field] = &[
+    ("currencyTypeName", Kind::String, true),
+    ("chaosEquivalent", Kind::Number, true),
+    ("pay", Kind::Object, false),
+    ("receive", Kind::Object, false),
+    ("paySparkLine", Kind::Object, false),
+    ("receiveSparkLine", Kind::Object, false),
+    ("lowConfidencePaySparkLine", Kind::Object, false),
+    ("lowConfidenceReceiveSparkLine", Kind::Object, false),
+    ("detailsId", Kind::String, false),
+];
+
+/// The fields of a line of an item overview.
+const ITEM_FIELDS: &[Field] = &[
+    ("name", Kind::String, true),
+    ("chaosValue", Kind::Number, true),
+    ("id", Kind::Number, false),
+    ("icon", Kind::String, false),
+    ("baseType", Kind::String, false),
+    ("variant", Kind::String, false),
+    ("levelRequired", Kind::Number, false),
+    ("links", Kind::Number, false),
+    ("itemClass", Kind::Number, false),
+    ("itemType", Kind::String, false),
+    ("gemLevel", Kind::Number, false),
+    ("gemQuality", Kind::Number, false),
+    ("corrupted", Kind::Bool, false),
+    ("mapTier", Kind::Number, false),
+    ("mapRegion", Kind::String, false),
+    ("stackSize", Kind::Number, false),
+    ("count", Kind::Number, false),
+    ("listingCount", Kind::Number, false),
+    ("exaltedValue", Kind::Number, false),
+    ("divineValue", Kind::Number, false),
+    ("sparkline", Kind::Object, false),
+    ("lowConfidenceSparkline", Kind::Object, false),
+    ("implicitModifiers", Kind::Array, false),
+    ("explicitModifiers", Kind::Array, false),
+    ("mutatedModifiers", Kind::Array, false),
+    ("flavourText", Kind::String, false),
+    ("prophecyText", Kind::String, false),
+    ("artFilename", Kind::String, false),
+    ("tradeInfo", Kind::Array, false),
+    ("tradeFilter", Kind::Object, false),
+    ("detailsId", Kind::String, false),
+];
+
+impl Kind {
+    /// The type of a value.
+    pub fn of(value: &Value) -> Kind {
+        match value {
+            Value::Null => Kind::Null,
+            Value::Bool(_) => Kind::Bool,
+            Value::Number(_) => Kind::Number,
+            Value::String(_) => Kind::String,
+            Value::Array(_) => Kind::Array,
+            Value::Object(_) => Kind::Object,
+        }
+    }
+}
+
+impl Drift {
+    /// No differences in a category.
+    pub fn new(category: Category) -> Drift {
+        Drift {
+            category: Some(category),
+            ..Drift::default()
+        }
+    }
+
+    /// Whether the response has the known shape.
+    pub fn is_empty(&self) -> bool {
+        self.unknown.is_empty() && self.missing.is_empty() && self.changed.is_empty()
+    }
+
+    /// Adds the differences of another response, keeping the types already found for a field.
+    pub fn extend(&mut self, other: Drift) {
+        self.unknown.extend(other.unknown);
+        self.missing.extend(other.missing);
+        for (field, kinds) in other.changed {
+            self.changed.entry(field).or_insert(kinds);
+        }
+        self.affected.extend(other.affected);
+    }
+}
+
+/// Checks the lines of a response against the known fields of its category.
+///
+/// In lenient mode, fields of the wrong type are removed from the lines, and lines missing a
+/// required field are replaced with `null` so they are skipped.
+pub(crate) fn check(category: Category, lines: &mut [Value], mode: Mode) -> Drift {
+    let fields = if category.is_currency() {
+        CURRENCY_FIELDS
+    } else {
+        ITEM_FIELDS
+    };
+    let mut drift = Drift::new(category);
+    for line in lines.iter_mut() {
+        let Value::Object(object) = line else {
+            drift
+                .changed
+                .insert(String::from("line"), (Kind::Object, Kind::of(line)));
+            continue;
+        };
+        let name = object
+            .get(fields[0].0)
+            .and_then(Value::as_str)
+            .unwrap_or("unnamed line")
+            .to_string();
+        for key in object.keys() {
+            if !fields.iter().any(|(field, _, _)| field == key) {
+                drift.unknown.insert(key.clone());
+            }
+        }
+        let mut missing = vec![];
+        for (field, kind, required) in fields {
+            let found = object.get(*field).map_or(Kind::Null, Kind::of);
+            if found == Kind::Null {
+                if *required {
+                    drift.missing.insert(field.to_string());
+                    missing.push(*field);
+                }
+            } else if found != *kind {
+                drift.changed.insert(field.to_string(), (*kind, found));
+                if *required {
+                    missing.push(*field);
+                } else if mode == Mode::Lenient {
+                    object.remove(*field);
+                    drift.affected.push((
+                        name.clone(),
+                        format!("ignored {}, a {} instead of a {}", field, found, kind),
+                    ));
+                }
+            }
+        }
+        if mode == Mode::Lenient && !missing.is_empty() {
+            drift
+                .affected
+                .push((name, format!("skipped, unusable {}", missing.join(", "))));
+            *line = Value::Null;
+        }
+    }
+    drift
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Kind::Null => "null",
+            Kind::Bool => "boolean",
+            Kind::Number => "number",
+            Kind::String => "string",
+            Kind::Array => "array",
+            Kind::Object => "object",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl fmt::Display for Drift {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut parts = vec![];
+        if !self.unknown.is_empty() {
+            parts.push(format!(
+                "unknown fields {}",
+                self.unknown.iter().cloned().collect::<Vec<_>>().join(", ")
+            ));
+        }
+        if !self.missing.is_empty() {
+            parts.push(format!(
+                "missing fields {}",
+                self.missing.iter().cloned().collect::<Vec<_>>().join(", ")
+            ));
+        }
+        for (field, (expected, found)) in &self.changed {
+            parts.push(format!("{} is {} instead of {}", field, found, expected));
+        }
+        match self.category {
+            Some(category) => write!(f, "{}: {}", category, parts.join("; ")),
+            None => write!(f, "{}", parts.join("; ")),
+        }
+    }
+}
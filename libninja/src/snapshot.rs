@@ -0,0 +1,251 @@
+//! Fetched prices kept on disk, so they can be reused offline and pinned for reproducible filters.
+use crate::category::Category;
+use crate::error::Error;
+use crate::price::Price;
+use crate::schema::Drift;
+use crate::source::PriceSource;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The file in a store's directory that holds the HTTP cache.
+const HTTP_CACHE_FILE: &str = "http_cache.json";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// The prices of a category in a league at one point in time.
+pub struct Snapshot {
+    /// The league the prices are from.
+    pub league: String,
+
+    /// The category of the prices.
+    pub category: Category,
+
+    /// When the prices were fetched, in milliseconds since the Unix epoch.
+    pub timestamp: u64,
+
+    /// The prices.
+    pub prices: Vec<Price>,
+
+    /// How the response differed from the known shape, if it did. Only a lenient source
+    /// returns prices from a response that differs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub drift: Option<Drift>,
+}
+
+/// Snapshots stored on disk, one file per league, category and timestamp.
+///
+/// Timestamps are in milliseconds, so snapshots taken in quick succession get their own files.
+///
+/// # Example
+/// ```
+/// # use libninja::snapshot::{Snapshot, SnapshotStore};
+/// # use libninja::category::Category;
+/// # use libninja::client::Client;
+/// let directory = std::env::temp_dir().join("libninja_snapshot_store");
+/// # let _ = std::fs::remove_dir_all(&directory);
+/// let store = SnapshotStore::new(&directory);
+/// store.save(&Snapshot {
+///     league: String::from("Standard"),
+///     category: Category::Currency,
+///     timestamp: 1_700_000_000_000,
+///     prices: vec![],
+///     drift: None,
+/// }).unwrap();
+///
+/// // Offline, the newest snapshot is used however old it is.
+/// let offline = SnapshotStore::new(&directory).offline(true);
+/// let snapshot = offline.get(&Client::new(), "Standard", Category::Currency).unwrap();
+/// # assert_eq!(snapshot.timestamp, 1_700_000_000_000);
+/// # assert!(offline.get(&Client::new(), "Standard", Category::Scarab).is_err());
+///
+/// // Pinned, the newest snapshot at or before the pin is used.
+/// let pinned = SnapshotStore::new(&directory).pin(1_700_000_500_000);
+/// # assert_eq!(pinned.get(&Client::new(), "Standard", Category::Currency).unwrap().timestamp, 1_700_000_000_000);
+/// # assert!(SnapshotStore::new(&directory).pin(1).get(&Client::new(), "Standard", Category::Currency).is_err());
+/// ```
+pub struct SnapshotStore {
+    pub(crate) directory: PathBuf,
+    ttl: Duration,
+    pub(crate) offline: bool,
+    pub(crate) pin: Option<u64>,
+}
+
+impl SnapshotStore {
+    /// Creates a store in a directory. Snapshots are fresh for an hour.
+    pub fn new(directory: impl Into<PathBuf>) -> SnapshotStore {
+        SnapshotStore {
+            directory: directory.into(),
+            ttl: Duration::from_secs(60 * 60),
+            offline: false,
+            pin: None,
+        }
+    }
+
+    /// Sets how long a snapshot is used before fetching a new one.
+    pub fn ttl(mut self, ttl: Duration) -> SnapshotStore {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Only reads snapshots, never fetching, however old the snapshots are.
+    pub fn offline(mut self, offline: bool) -> SnapshotStore {
+        self.offline = offline;
+        self
+    }
+
+    /// Only uses the newest snapshot taken at or before the timestamp, never fetching.
+    pub fn pin(mut self, timestamp: u64) -> SnapshotStore {
+        self.pin = Some(timestamp);
+        self
+    }
+
+    /// Writes a snapshot, returning the file it was written to.
+    pub fn save(&self, snapshot: &Snapshot) -> Result<PathBuf, Error> {
+        let directory = self.category_directory(&snapshot.league, snapshot.category);
+        fs::create_dir_all(&directory)?;
+        let path = directory.join(format!("{}.json", snapshot.timestamp));
+        fs::write(&path, serde_json::to_string(snapshot)?)?;
+        Ok(path)
+    }
+
+    /// Reads the snapshot taken at a timestamp.
+    pub fn load(
+        &self,
+        league: &str,
+        category: Category,
+        timestamp: u64,
+    ) -> Result<Snapshot, Error> {
+        let path = self
+            .category_directory(league, category)
+            .join(format!("{}.json", timestamp));
+        if !path.exists() {
+            return Err(Error::MissingSnapshot(
+                league.to_string(),
+                category,
+                timestamp,
+            ));
+        }
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /// The timestamps of every snapshot of a category, oldest first.
+    pub fn timestamps(&self, league: &str, category: Category) -> Result<Vec<u64>, Error> {
+        let directory = self.category_directory(league, category);
+        if !directory.exists() {
+            return Ok(vec![]);
+        }
+        let mut timestamps = vec![];
+        for entry in fs::read_dir(directory)? {
+            let path = entry?.path();
+            if path
+                .extension()
+                .is_some_and(|extension| extension == "json")
+            {
+                if let Some(timestamp) = path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .and_then(|stem| stem.parse().ok())
+                {
+                    timestamps.push(timestamp);
+                }
+            }
+        }
+        timestamps.sort();
+        Ok(timestamps)
+    }
+
+    /// The newest snapshot of a category, if there is one.
+    pub fn latest(&self, league: &str, category: Category) -> Result<Option<Snapshot>, Error> {
+        match self.timestamps(league, category)?.last() {
+            Some(timestamp) => Ok(Some(self.load(league, category, *timestamp)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// The prices of a category, fetching them from the source and saving them if there is no
+    /// fresh snapshot.
+    ///
+    /// When pinned or offline, the store never fetches.
+    pub fn get<S: PriceSource + ?Sized>(
+        &self,
+        source: &S,
+        league: &str,
+        category: Category,
+    ) -> Result<Snapshot, Error> {
+        if let Some(snapshot) = self.stored(league, category)? {
+            return Ok(snapshot);
+        }
+        let (prices, drift) = source.prices_with_drift(league, category)?;
+        self.record(league, category, prices, drift)
+    }
+
+    /// The snapshot to use without fetching: the pinned one, the newest one when offline, or
+    /// the newest one if it is fresh. `None` if the prices should be fetched.
+    pub(crate) fn stored(
+        &self,
+        league: &str,
+        category: Category,
+    ) -> Result<Option<Snapshot>, Error> {
+        if let Some(pin) = self.pin {
+            let timestamp = self
+                .timestamps(league, category)?
+                .into_iter()
+                .rfind(|timestamp| *timestamp <= pin)
+                .ok_or_else(|| Error::MissingSnapshot(league.to_string(), category, pin))?;
+            return self.load(league, category, timestamp).map(Some);
+        }
+        match self.latest(league, category)? {
+            Some(snapshot) if self.offline => Ok(Some(snapshot)),
+            None if self.offline => Err(Error::Offline(league.to_string(), category)),
+            Some(snapshot)
+                if now().saturating_sub(snapshot.timestamp) < self.ttl.as_millis() as u64 =>
+            {
+                Ok(Some(snapshot))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Saves fetched prices as the newest snapshot, keeping the drift if there is any.
+    pub(crate) fn record(
+        &self,
+        league: &str,
+        category: Category,
+        prices: Vec<Price>,
+        drift: Drift,
+    ) -> Result<Snapshot, Error> {
+        let snapshot = Snapshot {
+            league: league.to_string(),
+            category,
+            timestamp: now(),
+            prices,
+            drift: Some(drift).filter(|drift| !drift.is_empty()),
+        };
+        self.save(&snapshot)?;
+        Ok(snapshot)
+    }
+
+    /// The file the HTTP cache is kept in next to the snapshots, for [Http::cache_file], so
+    /// unchanged prices are revalidated rather than downloaded again on the next run.
+    ///
+    /// [Http::cache_file]: crate::http::Http::cache_file
+    pub fn http_cache(&self) -> PathBuf {
+        self.directory.join(HTTP_CACHE_FILE)
+    }
+
+    /// The directory holding the snapshots of a category.
+    fn category_directory(&self, league: &str, category: Category) -> PathBuf {
+        self.directory
+            .join(league.replace(['/', '\\'], "_"))
+            .join(category.to_string())
+    }
+}
+
+/// The current time in milliseconds since the Unix epoch.
+pub(crate) fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0)
+}
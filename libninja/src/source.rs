@@ -0,0 +1,353 @@
+//! Where prices come from.
+//!
+//! Tiering only needs prices, so anything implementing [PriceSource] can stand in for poe.ninja:
+//! a hand-written price sheet, or several sources combined item by item.
+use crate::category::Category;
+use crate::client::Client;
+use crate::error::Error;
+use crate::price::{Price, PriceKey};
+use crate::schema::Drift;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// A source of current prices.
+pub trait PriceSource {
+    /// The current prices of a category in a league.
+    fn prices(&self, league: &str, category: Category) -> Result<Vec<Price>, Error>;
+
+    /// The current prices of a category in a league, with how the data differs from the shape
+    /// the source expects. Sources that cannot tell report no drift.
+    fn prices_with_drift(
+        &self,
+        league: &str,
+        category: Category,
+    ) -> Result<(Vec<Price>, Drift), Error> {
+        Ok((self.prices(league, category)?, Drift::new(category)))
+    }
+}
+
+impl PriceSource for Client {
+    fn prices(&self, league: &str, category: Category) -> Result<Vec<Price>, Error> {
+        self.fetch(league, category)
+    }
+
+    fn prices_with_drift(
+        &self,
+        league: &str,
+        category: Category,
+    ) -> Result<(Vec<Price>, Drift), Error> {
+        self.fetch_with_drift(league, category)
+    }
+}
+
+impl<S: PriceSource + ?Sized> PriceSource for Box<S> {
+    fn prices(&self, league: &str, category: Category) -> Result<Vec<Price>, Error> {
+        (**self).prices(league, category)
+    }
+
+    fn prices_with_drift(
+        &self,
+        league: &str,
+        category: Category,
+    ) -> Result<(Vec<Price>, Drift), Error> {
+        (**self).prices_with_drift(league, category)
+    }
+}
+
+/// Columns of a CSV price sheet that hold text rather than numbers or booleans.
+const TEXT_COLUMNS: [&str; 5] = ["category", "league", "name", "base_type", "variant"];
+
+#[derive(Deserialize)]
+struct Row {
+    category: Category,
+    #[serde(default)]
+    league: Option<String>,
+    #[serde(flatten)]
+    price: Price,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Prices read from a hand-written price sheet, in JSON or CSV.
+///
+/// Each row has a `category`, the fields of a [Price], and optionally a `league`. Rows without
+/// a league apply to every league. A JSON sheet is an array of rows; a CSV sheet has a header
+/// naming the columns, such as `category,name,chaos_value`. Files ending in `.csv` are read as
+/// CSV and everything else as JSON. The file is read on every call, so it can be edited while
+/// in use.
+///
+/// # Example
+/// ```
+/// # use libninja::source::{FileSource, PriceSource};
+/// # use libninja::Category;
+/// let path = std::env::temp_dir().join("libninja_prices.csv");
+/// std::fs::write(&path, "category,league,name,chaos_value,corrupted\n\
+///     Currency,,Divine Orb,200,\n\
+///     UniqueAccessory,Settlers,\"Mageblood, the Belt\",40000,false\n").unwrap();
+/// let source = FileSource::new(&path);
+/// let prices = source.prices("Standard", Category::Currency).unwrap();
+/// # assert_eq!(prices[0].chaos_value, 200.0);
+/// # assert!(source.prices("Standard", Category::UniqueAccessory).unwrap().is_empty());
+/// # assert_eq!(source.prices("Settlers", Category::UniqueAccessory).unwrap()[0].name, "Mageblood, the Belt");
+/// ```
+pub struct FileSource {
+    path: PathBuf,
+}
+
+impl FileSource {
+    /// A price sheet at a path.
+    pub fn new(path: impl Into<PathBuf>) -> FileSource {
+        FileSource { path: path.into() }
+    }
+
+    /// Every row of the sheet.
+    fn rows(&self) -> Result<Vec<Row>, Error> {
+        let text = fs::read_to_string(&self.path)?;
+        if self
+            .path
+            .extension()
+            .is_some_and(|extension| extension == "csv")
+        {
+            csv_rows(&text)
+        } else {
+            Ok(serde_json::from_str(&text)?)
+        }
+    }
+}
+
+impl PriceSource for FileSource {
+    fn prices(&self, league: &str, category: Category) -> Result<Vec<Price>, Error> {
+        Ok(self
+            .rows()?
+            .into_iter()
+            .filter(|row| row.category == category)
+            .filter(|row| row.league.as_deref().is_none_or(|name| name == league))
+            .map(|row| row.price)
+            .collect())
+    }
+}
+
+/// Reads the rows of a CSV price sheet, going through JSON so the columns are checked the same
+/// way as in a JSON sheet.
+fn csv_rows(text: &str) -> Result<Vec<Row>, Error> {
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+    let header = match lines.next() {
+        Some((_, header)) => csv_fields(header),
+        None => return Ok(vec![]),
+    };
+    lines
+        .map(|(number, line)| {
+            let sheet_error = |message: String| Error::Sheet(number + 1, message);
+            let fields = csv_fields(line);
+            if fields.len() > header.len() {
+                return Err(sheet_error(format!(
+                    "{} fields but {} columns",
+                    fields.len(),
+                    header.len()
+                )));
+            }
+            let mut row = Map::new();
+            for (column, field) in header.iter().zip(fields) {
+                if field.is_empty() {
+                    continue;
+                }
+                let value = if TEXT_COLUMNS.contains(&column.as_str()) {
+                    Value::String(field)
+                } else {
+                    serde_json::from_str(&field)
+                        .map_err(|_| sheet_error(format!("invalid {} `{}`", column, field)))?
+                };
+                row.insert(column.clone(), value);
+            }
+            serde_json::from_value(Value::Object(row)).map_err(|e| sheet_error(e.to_string()))
+        })
+        .collect()
+}
+
+/// Splits a CSV line into its fields, unquoting quoted fields.
+fn csv_fields(line: &str) -> Vec<String> {
+    let mut fields = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.trim_end_matches('\r').chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+        .into_iter()
+        .map(|field| field.trim().to_string())
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// How a [Composite] source combines items priced by more than one source.
+pub enum Combine {
+    /// The price from the first source that prices the item.
+    Prefer,
+
+    /// The mean of the prices from every source that prices the item.
+    Mean,
+}
+
+/// Several sources combined item by item.
+///
+/// Items priced by only one source keep that price. A source that fails is skipped, so one
+/// source going down does not stop the others; only if every source fails is the first error
+/// returned. [Composite::merge] also returns the errors of the sources that were skipped, and
+/// the drift of every source that succeeded.
+///
+/// # Example
+/// ```
+/// # use libninja::source::{Combine, Composite, FileSource, PriceSource};
+/// # use libninja::Category;
+/// let directory = std::env::temp_dir();
+/// std::fs::write(directory.join("libninja_manual.json"), r#"[{"category":"Currency","name":"Divine Orb","chaos_value":180.0}]"#).unwrap();
+/// std::fs::write(directory.join("libninja_other.json"), r#"[{"category":"Currency","name":"Divine Orb","chaos_value":220.0},{"category":"Currency","name":"Chaos Orb","chaos_value":1.0}]"#).unwrap();
+/// let source = Composite::new(Combine::Prefer)
+///     .source(FileSource::new(directory.join("libninja_manual.json")))
+///     .source(FileSource::new(directory.join("libninja_missing.json")))
+///     .source(FileSource::new(directory.join("libninja_other.json")));
+/// let prices = source.prices("Standard", Category::Currency).unwrap();
+/// # assert_eq!(prices.len(), 2);
+/// # assert_eq!(prices.iter().find(|price| price.name == "Divine Orb").unwrap().chaos_value, 180.0);
+/// let merged = source.merge("Standard", Category::Currency).unwrap();
+/// # assert_eq!(merged.failures.iter().map(|(index, _)| *index).collect::<Vec<_>>(), [1]);
+/// let mean = Composite { combine: Combine::Mean, ..source };
+/// # assert_eq!(mean.prices("Standard", Category::Currency).unwrap().iter().find(|price| price.name == "Divine Orb").unwrap().chaos_value, 200.0);
+/// ```
+///
+/// The drift of each source is carried through:
+/// ```
+/// # use libninja::source::{Combine, Composite, PriceSource};
+/// # use libninja::schema::Drift;
+/// # use libninja::{Category, Error, Price};
+/// struct Drifting;
+///
+/// impl PriceSource for Drifting {
+///     fn prices(&self, league: &str, category: Category) -> Result<Vec<Price>, Error> {
+///         Ok(self.prices_with_drift(league, category)?.0)
+///     }
+///
+///     fn prices_with_drift(&self, _: &str, category: Category) -> Result<(Vec<Price>, Drift), Error> {
+///         let mut drift = Drift::new(category);
+///         drift.unknown.insert(String::from("chaosPrice"));
+///         Ok((vec![], drift))
+///     }
+/// }
+///
+/// let source = Composite::new(Combine::Prefer).source(Drifting).source(Drifting);
+/// let (_, drift) = source.prices_with_drift("Standard", Category::Scarab).unwrap();
+/// # assert_eq!(drift.to_string(), "Scarab: unknown fields chaosPrice");
+/// ```
+pub struct Composite {
+    /// The sources, most preferred first.
+    pub sources: Vec<Box<dyn PriceSource>>,
+
+    /// How items priced by more than one source are combined.
+    pub combine: Combine,
+}
+
+#[derive(Debug)]
+/// The prices combined by a [Composite], and the sources it skipped.
+pub struct Merged {
+    /// The combined prices.
+    pub prices: Vec<Price>,
+
+    /// The index of each source that failed, with its error.
+    pub failures: Vec<(usize, Error)>,
+
+    /// How the data of the sources that succeeded differs from the shape they expect.
+    pub drift: Drift,
+}
+
+impl Composite {
+    /// A composite without sources.
+    pub fn new(combine: Combine) -> Composite {
+        Composite {
+            sources: vec![],
+            combine,
+        }
+    }
+
+    /// Adds a source, less preferred than those already added.
+    pub fn source(mut self, source: impl PriceSource + 'static) -> Composite {
+        self.sources.push(Box::new(source));
+        self
+    }
+
+    /// The combined prices of a category, with the sources that failed and were skipped.
+    pub fn merge(&self, league: &str, category: Category) -> Result<Merged, Error> {
+        let mut items = BTreeMap::<PriceKey, Vec<Price>>::new();
+        let mut order = vec![];
+        let mut failures = vec![];
+        let mut drift = Drift::new(category);
+        let mut succeeded = false;
+        for (index, source) in self.sources.iter().enumerate() {
+            match source.prices_with_drift(league, category) {
+                Ok((prices, found)) => {
+                    succeeded = true;
+                    drift.extend(found);
+                    for price in prices {
+                        let key = price.key();
+                        let prices = items.entry(key.clone()).or_default();
+                        if prices.is_empty() {
+                            order.push(key);
+                        }
+                        prices.push(price);
+                    }
+                }
+                Err(e) => failures.push((index, e)),
+            }
+        }
+        if !succeeded && !failures.is_empty() {
+            return Err(failures.remove(0).1);
+        }
+        let prices = order
+            .into_iter()
+            .filter_map(|key| items.remove(&key))
+            .map(|prices| {
+                let count = prices.len() as f64;
+                let mean = prices.iter().map(|price| price.chaos_value).sum::<f64>() / count;
+                let mut price = prices.into_iter().next().unwrap_or_default();
+                if self.combine == Combine::Mean {
+                    price.chaos_value = mean;
+                }
+                price
+            })
+            .collect();
+        Ok(Merged {
+            prices,
+            failures,
+            drift,
+        })
+    }
+}
+
+impl PriceSource for Composite {
+    fn prices(&self, league: &str, category: Category) -> Result<Vec<Price>, Error> {
+        self.merge(league, category).map(|merged| merged.prices)
+    }
+
+    fn prices_with_drift(
+        &self,
+        league: &str,
+        category: Category,
+    ) -> Result<(Vec<Price>, Drift), Error> {
+        self.merge(league, category)
+            .map(|merged| (merged.prices, merged.drift))
+    }
+}
@@ -0,0 +1,156 @@
+//! Keeping prices from thin markets out of the top tiers.
+//!
+//! A single listing can put an item in the top tier, so prices are checked against a
+//! [ConfidencePolicy] before they are tiered.
+use crate::tier::TierList;
+use libninja::Price;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+/// Which prices are trusted enough to tier.
+///
+/// Prices without a listing count are trusted, so snapshots taken before listings were
+/// recorded are tiered as before.
+///
+/// # Example
+/// ```
+/// # use rongo::confidence::ConfidencePolicy;
+/// # use rongo::tier::TierList;
+/// # use libninja::Price;
+/// let price = |name: &str, chaos_value, listings| Price {
+///     name: String::from(name),
+///     chaos_value,
+///     listings: Some(listings),
+///     ..Price::default()
+/// };
+/// let prices = [price("Mageblood", 40000.0, 150), price("Troll", 5000.0, 8), price("Junk", 1.0, 1)];
+/// let tiers = TierList::new(vec![1000.0, 100.0, 10.0]);
+/// let policy = ConfidencePolicy { min_listings: 3, thin_market: Some((20, 2)), ..ConfidencePolicy::default() };
+///
+/// let (kept, report) = policy.apply(&prices, &tiers);
+/// # assert_eq!(kept.len(), 2);
+/// # assert_eq!(tiers.tier(kept[1].chaos_value), 2);
+/// # assert_eq!(report.to_string(), "excluded Junk: 1 listings, fewer than 3\ncapped Troll: 5000 to 10 chaos, 8 listings\n");
+/// ```
+pub struct ConfidencePolicy {
+    /// Prices from fewer listings are not tiered.
+    pub min_listings: u32,
+
+    /// Whether prices poe.ninja marks as low confidence are not tiered.
+    pub ignore_low_confidence: bool,
+
+    /// Prices from fewer than this many listings are tiered no better than this tier.
+    pub thin_market: Option<(u32, usize)>,
+}
+
+impl Default for ConfidencePolicy {
+    fn default() -> ConfidencePolicy {
+        ConfidencePolicy {
+            min_listings: 0,
+            ignore_low_confidence: true,
+            thin_market: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Why a price was not tiered.
+pub enum Reason {
+    /// It was taken from the first number of listings, fewer than the second.
+    TooFewListings(u32, u32),
+
+    /// poe.ninja marks it as low confidence.
+    LowConfidence,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+/// The prices a [ConfidencePolicy] left out or lowered.
+pub struct ConfidenceReport {
+    /// Prices that were not tiered.
+    pub excluded: Vec<(Price, Reason)>,
+
+    /// Prices that were lowered, with the value they were lowered to.
+    pub capped: Vec<(Price, f64)>,
+}
+
+impl ConfidencePolicy {
+    /// The prices to tier, with capped prices lowered to the threshold of their cap tier, and a
+    /// report of what was left out or lowered.
+    pub fn apply(&self, prices: &[Price], tiers: &TierList) -> (Vec<Price>, ConfidenceReport) {
+        let mut kept = vec![];
+        let mut report = ConfidenceReport::default();
+        for price in prices {
+            if self.ignore_low_confidence && price.low_confidence {
+                report.excluded.push((price.clone(), Reason::LowConfidence));
+                continue;
+            }
+            let listings = match price.listings {
+                Some(listings) => listings,
+                None => {
+                    kept.push(price.clone());
+                    continue;
+                }
+            };
+            if listings < self.min_listings {
+                report.excluded.push((
+                    price.clone(),
+                    Reason::TooFewListings(listings, self.min_listings),
+                ));
+                continue;
+            }
+            let cap = self
+                .thin_market
+                .filter(|(thin, _)| listings < *thin)
+                .map(|(_, tier)| tiers.thresholds.get(tier).copied().unwrap_or(0.0));
+            match cap {
+                Some(cap) if price.chaos_value > cap => {
+                    report.capped.push((price.clone(), cap));
+                    kept.push(Price {
+                        chaos_value: cap,
+                        ..price.clone()
+                    });
+                }
+                _ => kept.push(price.clone()),
+            }
+        }
+        (kept, report)
+    }
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Reason::TooFewListings(listings, min) => {
+                write!(f, "{} listings, fewer than {}", listings, min)
+            }
+            Reason::LowConfidence => write!(f, "low confidence"),
+        }
+    }
+}
+
+impl fmt::Display for ConfidenceReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (price, reason) in &self.excluded {
+            writeln!(f, "excluded {}: {}", label(price), reason)?;
+        }
+        for (price, cap) in &self.capped {
+            writeln!(
+                f,
+                "capped {}: {} to {} chaos, {} listings",
+                label(price),
+                price.chaos_value,
+                cap,
+                price.listings.unwrap_or(0)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The name of a price, with its variant if it has one.
+fn label(price: &Price) -> String {
+    match &price.variant {
+        Some(variant) => format!("{} ({})", price.name, variant),
+        None => price.name.clone(),
+    }
+}
@@ -0,0 +1,313 @@
+//! The generators a filter is built from.
+//!
+//! A [Config] builds a whole filter from prices of each category. Trade prices come from
+//! poe.ninja, and [Config::ssf_filter] builds the solo self-found alternative from the same
+//! generators and tiers.
+use crate::generate::base::Bases;
+use crate::generate::cluster::ClusterJewels;
+use crate::generate::currency::Stacks;
+use crate::generate::divination::Cards;
+use crate::generate::gem::Gems;
+use crate::generate::leveling::Leveling;
+use crate::generate::map::Maps;
+use crate::generate::recipe::{Collected, Recipe};
+use crate::generate::unique::Uniques;
+use crate::profile::Profile;
+use crate::ssf::Ssf;
+use crate::tier::{Assignment, Hysteresis, TierList};
+use libfilter::block::Block;
+use libfilter::filter::Filter;
+use libfilter::section::Section;
+use libninja::{Category, Price};
+use std::convert::Infallible;
+
+/// The categories whose prices the stackable currency blocks are built from. poe.ninja prices
+/// catalysts with the rest of the currency.
+pub const STACK_CATEGORIES: [Category; 7] = [
+    Category::Currency,
+    Category::Scarab,
+    Category::Fossil,
+    Category::Resonator,
+    Category::Essence,
+    Category::Oil,
+    Category::DeliriumOrb,
+];
+
+/// The categories whose prices the map blocks are built from.
+pub const MAP_CATEGORIES: [Category; 4] = [
+    Category::Map,
+    Category::BlightedMap,
+    Category::BlightRavagedMap,
+    Category::UniqueMap,
+];
+
+/// The categories whose prices the unique blocks are built from.
+pub const UNIQUE_CATEGORIES: [Category; 5] = [
+    Category::UniqueWeapon,
+    Category::UniqueArmour,
+    Category::UniqueAccessory,
+    Category::UniqueJewel,
+    Category::UniqueFlask,
+];
+
+#[derive(Debug, Clone, PartialEq)]
+/// The generators of a filter, each with its own tiers.
+///
+/// Sections without blocks are left out, and the profile, if there is one, is applied to the
+/// whole filter last.
+///
+/// # Example
+/// ```
+/// # use rongo::config::Config;
+/// # use rongo::ssf::Ssf;
+/// let config = Config::default();
+/// let filter = config.ssf_filter(&Ssf::curated());
+/// # let headers = filter.sections.iter().map(|section| section.description.as_str()).collect::<Vec<_>>();
+/// # assert_eq!(headers, ["Currency", "Bases", "Uniques", "Leveling"]);
+/// # let blocks = filter.blocks().map(|block| block.to_string()).collect::<Vec<_>>();
+/// # assert!(blocks.iter().any(|block| block.starts_with("Show # $type->currency $tier->t0\n\tClass \"Stackable Currency\"\n\tBaseType == \"Divine Orb\" \"Exalted Orb\" \"Mirror of Kalandra\"")));
+/// # assert!(blocks.iter().any(|block| block.starts_with("Show # $type->uniques $tier->t0\n\tRarity == Unique\n\tBaseType == \"Carnal Armour\" \"Leather Belt\" \"Prismatic Jewel\"")));
+/// ```
+pub struct Config {
+    /// The stackable currency generator.
+    pub currency: Stacks,
+
+    /// The divination card generator.
+    pub cards: Cards,
+
+    /// The skill gem generator.
+    pub gems: Gems,
+
+    /// The map generator.
+    pub maps: Maps,
+
+    /// The cluster jewel generator.
+    pub clusters: ClusterJewels,
+
+    /// The crafting base generator.
+    pub bases: Bases,
+
+    /// The unique generator.
+    pub uniques: Uniques,
+
+    /// The leveling generator, if leveling blocks are wanted.
+    pub leveling: Option<Leveling>,
+
+    /// The vendor recipe generator, if recipe blocks are wanted.
+    pub recipe: Option<Recipe>,
+
+    /// The items already collected for the recipe.
+    pub collected: Collected,
+
+    /// The build the filter is tailored to, if any.
+    pub profile: Option<Profile>,
+}
+
+impl Config {
+    /// Every generator with the same tiers, with leveling blocks but no recipe or profile.
+    pub fn new(tiers: TierList) -> Config {
+        Config {
+            currency: Stacks::new(tiers.clone()),
+            cards: Cards::new(tiers.clone()),
+            gems: Gems::new(tiers.clone()),
+            maps: Maps::new(tiers.clone()),
+            clusters: ClusterJewels::new(tiers.clone()),
+            bases: Bases::new(tiers.clone()),
+            uniques: Uniques::new(tiers),
+            leveling: Some(Leveling::new()),
+            recipe: None,
+            collected: Collected::default(),
+            profile: None,
+        }
+    }
+
+    /// Sets the vendor recipe and the items already collected for it.
+    pub fn recipe(mut self, recipe: Recipe, collected: Collected) -> Config {
+        self.recipe = Some(recipe);
+        self.collected = collected;
+        self
+    }
+
+    /// Sets the build the filter is tailored to.
+    pub fn profile(mut self, profile: Profile) -> Config {
+        self.profile = Some(profile);
+        self
+    }
+
+    /// The tiers the prices of a category are sorted into.
+    ///
+    /// # Example
+    /// ```
+    /// # use rongo::config::Config;
+    /// # use libninja::Category;
+    /// let config = Config::default();
+    /// # assert_eq!(config.tiers(Category::Fossil), &config.currency.tiers);
+    /// # assert_eq!(config.tiers(Category::UniqueMap), &config.maps.tiers);
+    /// # assert_eq!(config.tiers(Category::UniqueFlask), &config.uniques.tiers);
+    /// ```
+    pub fn tiers(&self, category: Category) -> &TierList {
+        match category {
+            category if STACK_CATEGORIES.contains(&category) => &self.currency.tiers,
+            category if MAP_CATEGORIES.contains(&category) => &self.maps.tiers,
+            Category::DivinationCard => &self.cards.tiers,
+            Category::SkillGem => &self.gems.tiers,
+            Category::ClusterJewel => &self.clusters.tiers,
+            Category::BaseType => &self.bases.tiers,
+            _ => &self.uniques.tiers,
+        }
+    }
+
+    /// The categories the filter is built from, in the order [Config::filter] asks for them.
+    pub fn categories() -> impl Iterator<Item = Category> {
+        STACK_CATEGORIES
+            .into_iter()
+            .chain([Category::DivinationCard, Category::SkillGem])
+            .chain(MAP_CATEGORIES)
+            .chain([Category::ClusterJewel, Category::BaseType])
+            .chain(UNIQUE_CATEGORIES)
+    }
+
+    /// Holds the items of a category in their previous tiers until the hysteresis lets them
+    /// move, and returns the assignment to keep for the next update.
+    ///
+    /// Each generator holds the values it tiers, such as each stack of a currency, so the
+    /// prices themselves are left as they are.
+    ///
+    /// # Example
+    /// ```
+    /// # use rongo::config::Config;
+    /// # use rongo::tier::{Assignment, Hysteresis};
+    /// # use libninja::{Category, Price};
+    /// let mut config = Config::default();
+    /// let divine = |chaos_value| Price { name: String::from("Divine Orb"), chaos_value, ..Price::default() };
+    /// let first = config.hold(Category::Currency, &[divine(100.0)], &Assignment::default(), &Hysteresis::default());
+    /// let second = config.hold(Category::Currency, &[divine(150.0)], &first, &Hysteresis::default());
+    /// # assert_eq!(second.tiers["Divine Orb x2"], 1);
+    /// # assert_eq!(second.tiers["Divine Orb x3"], 0);
+    /// # assert_eq!(config.currency.breakpoints(&divine(150.0)).into_iter().collect::<Vec<_>>(), [(0, 3), (1, 1)]);
+    /// ```
+    pub fn hold(
+        &mut self,
+        category: Category,
+        prices: &[Price],
+        previous: &Assignment,
+        hysteresis: &Hysteresis,
+    ) -> Assignment {
+        let tiered = match category {
+            category if STACK_CATEGORIES.contains(&category) => self.currency.tiered(prices),
+            category if MAP_CATEGORIES.contains(&category) => self.maps.tiered(prices),
+            Category::DivinationCard => self.cards.tiered(prices),
+            Category::SkillGem => self.gems.tiered(prices),
+            Category::ClusterJewel => self.clusters.tiered(prices),
+            Category::BaseType => self.bases.tiered(prices),
+            _ => self.uniques.tiered(prices),
+        };
+        let values = tiered.iter().map(|(key, value)| (key.as_str(), *value));
+        let assignment = self
+            .tiers(category)
+            .assign_with_hysteresis(values, previous, hysteresis);
+        let tiers = match category {
+            category if STACK_CATEGORIES.contains(&category) => &mut self.currency.tiers,
+            category if MAP_CATEGORIES.contains(&category) => &mut self.maps.tiers,
+            Category::DivinationCard => &mut self.cards.tiers,
+            Category::SkillGem => &mut self.gems.tiers,
+            Category::ClusterJewel => &mut self.clusters.tiers,
+            Category::BaseType => &mut self.bases.tiers,
+            _ => &mut self.uniques.tiers,
+        };
+        tiers.held.extend(assignment.tiers.clone());
+        assignment
+    }
+
+    /// Builds a filter from the prices of each category, or the first error getting them.
+    pub fn filter<E>(
+        &self,
+        mut prices: impl FnMut(Category) -> Result<Vec<Price>, E>,
+    ) -> Result<Filter, E> {
+        let mut all = |categories: &[Category]| -> Result<Vec<Price>, E> {
+            let mut all = vec![];
+            for category in categories {
+                all.extend(prices(*category)?);
+            }
+            Ok(all)
+        };
+        let mut sections = vec![
+            (
+                "0100",
+                "Currency",
+                self.currency.blocks(&all(&STACK_CATEGORIES)?),
+            ),
+            (
+                "0200",
+                "Divination Cards",
+                self.cards.blocks(&all(&[Category::DivinationCard])?),
+            ),
+            (
+                "0300",
+                "Gems",
+                self.gems.blocks(&all(&[Category::SkillGem])?),
+            ),
+            ("0400", "Maps", self.maps.blocks(&all(&MAP_CATEGORIES)?)),
+            (
+                "0500",
+                "Cluster Jewels",
+                self.clusters.blocks(&all(&[Category::ClusterJewel])?),
+            ),
+            (
+                "0600",
+                "Bases",
+                self.bases.blocks(&all(&[Category::BaseType])?),
+            ),
+            (
+                "0700",
+                "Uniques",
+                self.uniques.blocks(&all(&UNIQUE_CATEGORIES)?),
+            ),
+        ];
+        if let Some(leveling) = &self.leveling {
+            sections.push(("0800", "Leveling", leveling.blocks()));
+        }
+        if let Some(recipe) = &self.recipe {
+            sections.push(("0900", "Recipe", recipe.blocks(&self.collected)));
+        }
+        let mut filter = Filter::new();
+        for (header, description, blocks) in sections {
+            if !blocks.is_empty() {
+                filter.add_section(section(header, description, blocks));
+            }
+        }
+        if let Some(profile) = &self.profile {
+            profile.apply(&mut filter);
+        }
+        Ok(filter)
+    }
+
+    /// Builds the solo self-found filter, valuing items by usefulness instead of price.
+    pub fn ssf_filter(&self, ssf: &Ssf) -> Filter {
+        match self.filter(|category| Ok::<_, Infallible>(ssf.prices(category))) {
+            Ok(filter) => filter,
+            Err(never) => match never {},
+        }
+    }
+}
+
+impl Default for Config {
+    /// Tiers currency, bases and uniques from 300, 30 and 5 Chaos Orbs, the scale of the
+    /// solo self-found weights, and cards, gems, maps and cluster jewels lower, since few of
+    /// them are worth that much.
+    fn default() -> Config {
+        let tiers = |thresholds: [f64; 3]| TierList::new(thresholds.to_vec());
+        Config {
+            cards: Cards::new(tiers([100.0, 10.0, 2.0])),
+            gems: Gems::new(tiers([100.0, 20.0, 5.0])),
+            maps: Maps::new(tiers([50.0, 10.0, 2.0])),
+            clusters: ClusterJewels::new(tiers([100.0, 20.0, 5.0])),
+            ..Config::new(tiers([300.0, 30.0, 5.0]))
+        }
+    }
+}
+
+/// A section of blocks.
+fn section(header: &str, description: &str, blocks: Vec<Block>) -> Section {
+    Section::new(header).description(description).blocks(blocks)
+}
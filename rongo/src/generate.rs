@@ -0,0 +1,11 @@
+//! Generators that turn prices into filter blocks.
+
+pub mod base;
+pub mod cluster;
+pub mod currency;
+pub mod divination;
+pub mod gem;
+pub mod leveling;
+pub mod map;
+pub mod recipe;
+pub mod unique;
@@ -0,0 +1,160 @@
+//! Crafting bases, tiered by base type, item level and influence.
+//!
+//! poe.ninja prices bases at each item level from 82 and for each influence, so each priced
+//! combination worth highlighting gets a block. Bases in the lowest tier get no block, leaving
+//! them to the rest of the filter.
+use crate::style::Style;
+use crate::tier::{label, TierList};
+use libfilter::block::Block;
+use libfilter::{Influence, Operator, Rarity};
+use libninja::Price;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, PartialEq)]
+/// Generates blocks for crafting bases.
+///
+/// Blocks for bases with more influences come first, then higher item levels, so every item
+/// is matched by the most specific priced combination. Uninfluenced bases require
+/// `HasInfluence None`, so influenced items are never matched as plain ones.
+///
+/// # Example
+/// ```
+/// # use rongo::generate::base::Bases;
+/// # use rongo::style::Style;
+/// # use rongo::tier::TierList;
+/// # use libninja::Price;
+/// let base = |item_level, variant: Option<&str>, chaos_value| Price {
+///     name: String::from("Vermillion Ring"),
+///     item_type: Some(String::from("Ring")),
+///     item_level: Some(item_level),
+///     variant: variant.map(String::from),
+///     chaos_value,
+///     ..Price::default()
+/// };
+/// let prices = [base(86, None, 15.0), base(86, Some("Hunter"), 120.0), base(84, None, 2.0)];
+/// let blocks = Bases::new(TierList::new(vec![100.0, 10.0]))
+///     .styles(vec![Style::plain(); 3])
+///     .cutoff("Ring", 84)
+///     .blocks(&prices);
+/// # assert_eq!(blocks.len(), 2);
+/// # assert_eq!(blocks[0].to_string(), "Show # $type->bases $tier->t0\n\tRarity <= Rare\n\tBaseType \"Vermillion Ring\"\n\tItemLevel >= 86\n\tHasInfluence Hunter");
+/// # assert_eq!(blocks[1].to_string(), "Show # $type->bases $tier->t1\n\tRarity <= Rare\n\tBaseType \"Vermillion Ring\"\n\tItemLevel >= 86\n\tHasInfluence None");
+/// ```
+pub struct Bases {
+    /// The value thresholds of the tiers.
+    pub tiers: TierList,
+
+    /// The style of each tier.
+    pub styles: Vec<Style>,
+
+    /// The lowest item level highlighted for item types without a cutoff.
+    pub min_item_level: u8,
+
+    /// The lowest item level highlighted for an item type, such as `Ring` or `Body Armour`.
+    pub cutoffs: BTreeMap<String, u8>,
+}
+
+impl Bases {
+    /// Tiers bases of every item level, styled with [Style::ladder].
+    pub fn new(tiers: TierList) -> Bases {
+        let styles = Style::ladder(tiers.thresholds.len() + 1);
+        Bases {
+            tiers,
+            styles,
+            min_item_level: 0,
+            cutoffs: BTreeMap::new(),
+        }
+    }
+
+    /// Sets the style of each tier.
+    pub fn styles(mut self, styles: Vec<Style>) -> Bases {
+        self.styles = styles;
+        self
+    }
+
+    /// Sets the lowest item level highlighted for item types without a cutoff.
+    pub fn min_item_level(mut self, item_level: u8) -> Bases {
+        self.min_item_level = item_level;
+        self
+    }
+
+    /// Sets the lowest item level highlighted for an item type.
+    pub fn cutoff(mut self, item_type: impl Into<String>, item_level: u8) -> Bases {
+        self.cutoffs.insert(item_type.into(), item_level);
+        self
+    }
+
+    /// The influences of a priced base, or `None` if its variant is not a set of influences.
+    fn influences(price: &Price) -> Option<Vec<Influence>> {
+        let mut influences = match &price.variant {
+            Some(variant) => variant
+                .split('/')
+                .map(|name| name.trim().parse().ok())
+                .collect::<Option<Vec<Influence>>>()?,
+            None => vec![],
+        };
+        influences.sort();
+        Some(influences)
+    }
+
+    /// The value each priced item is tiered by, keyed by its [label].
+    pub fn tiered(&self, prices: &[Price]) -> Vec<(String, f64)> {
+        prices
+            .iter()
+            .map(|price| (label(price), price.chaos_value))
+            .collect()
+    }
+
+    /// The blocks for the bases worth highlighting.
+    pub fn blocks(&self, prices: &[Price]) -> Vec<Block> {
+        let mut blocks = BTreeMap::<_, Vec<String>>::new();
+        for price in prices {
+            let Some(item_level) = price.item_level else {
+                continue;
+            };
+            let cutoff = price
+                .item_type
+                .as_ref()
+                .and_then(|item_type| self.cutoffs.get(item_type))
+                .copied()
+                .unwrap_or(self.min_item_level);
+            let tier = self.tiers.tier_of(&label(price), price.chaos_value);
+            if item_level < cutoff || tier >= self.tiers.thresholds.len() {
+                continue;
+            }
+            let Some(influences) = Bases::influences(price) else {
+                continue;
+            };
+            blocks
+                .entry((
+                    Reverse(influences.len()),
+                    influences,
+                    Reverse(item_level),
+                    tier,
+                ))
+                .or_default()
+                .push(price.name.clone());
+        }
+        blocks
+            .into_iter()
+            .map(|((_, influences, Reverse(item_level), tier), names)| {
+                let mut block = Block::show()
+                    .comment(format!("$type->bases $tier->t{}", tier))
+                    .rarity(Operator::LessThanOrEqual, Rarity::Rare)
+                    .base_type(names)
+                    .item_level_ge(item_level);
+                if influences.is_empty() {
+                    block = block.has_influence(Influence::None);
+                }
+                for influence in influences {
+                    block = block.has_influence(influence);
+                }
+                match self.styles.get(tier) {
+                    Some(style) => style.apply(block),
+                    None => block,
+                }
+            })
+            .collect()
+    }
+}
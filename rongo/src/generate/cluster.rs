@@ -0,0 +1,140 @@
+//! Cluster jewels, tiered by enchantment, passive count and item level.
+//!
+//! poe.ninja names cluster jewels by the passive their enchantment grants, and prices each
+//! passive count at a few item levels. More passives are not always better, since smaller
+//! jewels leave more room for notables, so passive counts are matched exactly.
+use crate::style::Style;
+use crate::tier::{label, TierList};
+use libfilter::block::Block;
+use libfilter::{Operator, Rarity};
+use libninja::Price;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, PartialEq)]
+/// Generates blocks for cluster jewels.
+///
+/// Blocks for higher item levels come first, so every jewel is matched by the highest priced
+/// item level it reaches. Jewels in the lowest tier get no block.
+///
+/// # Example
+/// ```
+/// # use rongo::generate::cluster::ClusterJewels;
+/// # use rongo::style::Style;
+/// # use rongo::tier::TierList;
+/// # use libninja::Price;
+/// let jewel = |name: &str, passives, item_level, chaos_value| Price {
+///     name: String::from(name),
+///     base_type: Some(String::from("Large Cluster Jewel")),
+///     variant: Some(format!("{} passives", passives)),
+///     item_level: Some(item_level),
+///     chaos_value,
+///     ..Price::default()
+/// };
+/// let prices = [
+///     jewel("Minion Damage", 8, 84, 150.0),
+///     jewel("Minion Damage", 8, 75, 20.0),
+///     jewel("Minion Damage", 12, 84, 1.0),
+/// ];
+/// let blocks = ClusterJewels::new(TierList::new(vec![100.0, 10.0]))
+///     .styles(vec![Style::plain(); 3])
+///     .blocks(&prices);
+/// # let blocks = blocks.iter().map(|block| block.to_string()).collect::<Vec<_>>();
+/// # assert_eq!(blocks, [
+/// #     "Show # $type->clusters $tier->t0\n\tRarity <= Rare\n\tBaseType \"Large Cluster Jewel\"\n\tItemLevel >= 84\n\tEnchantmentPassiveNode \"Minion Damage\"\n\tEnchantmentPassiveNum == 8",
+/// #     "Show # $type->clusters $tier->t1\n\tRarity <= Rare\n\tBaseType \"Large Cluster Jewel\"\n\tItemLevel >= 75\n\tEnchantmentPassiveNode \"Minion Damage\"\n\tEnchantmentPassiveNum == 8",
+/// # ]);
+/// ```
+pub struct ClusterJewels {
+    /// The value thresholds of the tiers.
+    pub tiers: TierList,
+
+    /// The style of each tier.
+    pub styles: Vec<Style>,
+}
+
+impl ClusterJewels {
+    /// Tiers cluster jewels by value, styled with [Style::ladder].
+    pub fn new(tiers: TierList) -> ClusterJewels {
+        let styles = Style::ladder(tiers.thresholds.len() + 1);
+        ClusterJewels { tiers, styles }
+    }
+
+    /// Sets the style of each tier.
+    pub fn styles(mut self, styles: Vec<Style>) -> ClusterJewels {
+        self.styles = styles;
+        self
+    }
+
+    /// The passive count of a priced cluster jewel, from a variant such as `8 passives`.
+    ///
+    /// # Example
+    /// ```
+    /// # use rongo::generate::cluster::ClusterJewels;
+    /// # use libninja::Price;
+    /// let jewel = Price {
+    ///     variant: Some(String::from("12 passives")),
+    ///     ..Price::default()
+    /// };
+    /// # assert_eq!(ClusterJewels::passives(&jewel), Some(12));
+    /// ```
+    pub fn passives(price: &Price) -> Option<u8> {
+        price
+            .variant
+            .as_deref()?
+            .split_whitespace()
+            .next()?
+            .parse()
+            .ok()
+    }
+
+    /// The value each priced item is tiered by, keyed by its [label].
+    pub fn tiered(&self, prices: &[Price]) -> Vec<(String, f64)> {
+        prices
+            .iter()
+            .map(|price| (label(price), price.chaos_value))
+            .collect()
+    }
+
+    /// The blocks for the cluster jewels worth highlighting.
+    pub fn blocks(&self, prices: &[Price]) -> Vec<Block> {
+        let mut jewels = BTreeMap::new();
+        for price in prices {
+            let (Some(item_level), Some(passives), Some(base_type)) = (
+                price.item_level,
+                ClusterJewels::passives(price),
+                &price.base_type,
+            ) else {
+                continue;
+            };
+            let tier = self.tiers.tier_of(&label(price), price.chaos_value);
+            if tier >= self.tiers.thresholds.len() {
+                continue;
+            }
+            let key = (
+                Reverse(item_level),
+                base_type.clone(),
+                price.name.clone(),
+                passives,
+            );
+            let best = jewels.entry(key).or_insert(tier);
+            *best = tier.min(*best);
+        }
+        jewels
+            .into_iter()
+            .map(|((Reverse(item_level), base_type, name, passives), tier)| {
+                let block = Block::show()
+                    .comment(format!("$type->clusters $tier->t{}", tier))
+                    .rarity(Operator::LessThanOrEqual, Rarity::Rare)
+                    .base_type([base_type])
+                    .item_level_ge(item_level)
+                    .enchantment_passive_node(name)
+                    .enchantment_passive_num(Operator::Equal, passives);
+                match self.styles.get(tier) {
+                    Some(style) => style.apply(block),
+                    None => block,
+                }
+            })
+            .collect()
+    }
+}
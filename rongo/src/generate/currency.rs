@@ -0,0 +1,205 @@
+//! Stackable currency, tiered by the value of the stack that dropped.
+//!
+//! Shards, scarabs, essences, fossils, resonators, oils, delirium orbs and catalysts drop in
+//! stacks, so each base type gets a block for every stack size at which the stack reaches a
+//! better tier, and base types that share a class, tier and stack size share a block.
+use crate::style::Style;
+use crate::tier::{stack_key, TierList};
+use libfilter::block::Block;
+use libninja::Price;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+/// The item class of a stackable base type.
+///
+/// # Example
+/// ```
+/// # use rongo::generate::currency::class;
+/// # assert_eq!(class("Primitive Chaotic Resonator"), "Delve Stackable Socketable Currency");
+/// # assert_eq!(class("Gilded Ambush Scarab"), "Map Fragments");
+/// # assert_eq!(class("Turbulent Catalyst"), "Stackable Currency");
+/// ```
+pub fn class(name: &str) -> &'static str {
+    if name.ends_with("Resonator") {
+        "Delve Stackable Socketable Currency"
+    } else if name.contains("Scarab") {
+        "Map Fragments"
+    } else {
+        "Stackable Currency"
+    }
+}
+
+/// Base types whose stacks are not 20, by name.
+const STACK_SIZES: [(&str, u8); 10] = [
+    ("Mirror of Kalandra", 10),
+    ("Divine Orb", 10),
+    ("Exalted Orb", 10),
+    ("Regal Orb", 10),
+    ("Orb of Augmentation", 30),
+    ("Orb of Scouring", 30),
+    ("Orb of Transmutation", 40),
+    ("Armourer's Scrap", 40),
+    ("Scroll of Wisdom", 40),
+    ("Portal Scroll", 40),
+];
+
+/// The largest stack of a stackable base type, or `None` if it is not known.
+///
+/// # Example
+/// ```
+/// # use rongo::generate::currency::stack_size;
+/// # assert_eq!(stack_size("Divine Orb"), Some(10));
+/// # assert_eq!(stack_size("Pristine Fossil"), Some(10));
+/// # assert_eq!(stack_size("Essence of Greed"), Some(9));
+/// # assert_eq!(stack_size("Chaos Shard"), None);
+/// ```
+pub fn stack_size(name: &str) -> Option<u8> {
+    if let Some((_, size)) = STACK_SIZES.iter().find(|(base, _)| *base == name) {
+        Some(*size)
+    } else if name.starts_with("Essence of") || name == "Remnant of Corruption" {
+        Some(9)
+    } else if ["Fossil", "Resonator", "Catalyst", " Oil", "Delirium Orb"]
+        .iter()
+        .any(|suffix| name.ends_with(suffix))
+    {
+        Some(10)
+    } else {
+        None
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Generates blocks for stackable currency.
+///
+/// # Example
+/// ```
+/// # use rongo::generate::currency::Stacks;
+/// # use rongo::style::Style;
+/// # use rongo::tier::TierList;
+/// # use libninja::Price;
+/// let price = |name: &str, chaos_value| Price { name: String::from(name), chaos_value, ..Price::default() };
+/// let prices = [price("Chaos Shard", 0.05), price("Regal Shard", 0.05), price("Oil Extractor", 3.0), price("Ambush Scarab", 1.0)];
+/// let blocks = Stacks::new(TierList::new(vec![5.0, 0.5]))
+///     .styles(vec![Style::plain(); 3])
+///     .limit("Oil Extractor", 1)
+///     .blocks(&prices);
+/// # assert_eq!(blocks.len(), 5);
+/// # assert_eq!(blocks[0].to_string(), "Show # $type->currency $tier->t0\n\tClass \"Map Fragments\"\n\tBaseType == \"Ambush Scarab\"\n\tStackSize >= 5");
+/// # assert_eq!(blocks[1].to_string(), "Show # $type->currency $tier->t1\n\tClass \"Stackable Currency\"\n\tBaseType == \"Chaos Shard\" \"Regal Shard\"\n\tStackSize >= 10");
+/// # assert_eq!(blocks[3].to_string(), "Show # $type->currency $tier->t1\n\tClass \"Stackable Currency\"\n\tBaseType == \"Oil Extractor\"");
+/// # assert_eq!(blocks[4].to_string(), "Show # $type->currency $tier->t2\n\tClass \"Stackable Currency\"\n\tBaseType == \"Chaos Shard\" \"Regal Shard\"");
+/// ```
+///
+/// Stacks are only considered up to the size the base type stacks to:
+/// ```
+/// # use rongo::generate::currency::Stacks;
+/// # use rongo::tier::TierList;
+/// # use libninja::Price;
+/// let divine = Price { name: String::from("Divine Orb"), chaos_value: 1.0, ..Price::default() };
+/// let stacks = Stacks::new(TierList::new(vec![15.0, 5.0]));
+/// # assert_eq!(stacks.breakpoints(&divine).into_iter().collect::<Vec<_>>(), [(1, 5), (2, 1)]);
+/// ```
+pub struct Stacks {
+    /// The value thresholds of the tiers.
+    pub tiers: TierList,
+
+    /// The style of each tier.
+    pub styles: Vec<Style>,
+
+    /// The largest stack considered for base types without a limit or a known [stack_size].
+    pub max_stack: u8,
+
+    /// The largest stack considered for a base type, by name, in place of its [stack_size].
+    pub limits: BTreeMap<String, u8>,
+}
+
+impl Stacks {
+    /// Tiers stacks up to the [stack_size] of each base type, or 20 items, styled with
+    /// [Style::ladder].
+    pub fn new(tiers: TierList) -> Stacks {
+        let styles = Style::ladder(tiers.thresholds.len() + 1);
+        Stacks {
+            tiers,
+            styles,
+            max_stack: 20,
+            limits: BTreeMap::new(),
+        }
+    }
+
+    /// Sets the style of each tier.
+    pub fn styles(mut self, styles: Vec<Style>) -> Stacks {
+        self.styles = styles;
+        self
+    }
+
+    /// Sets the largest stack considered for base types without a limit or a known size.
+    pub fn max_stack(mut self, max_stack: u8) -> Stacks {
+        self.max_stack = max_stack;
+        self
+    }
+
+    /// Sets the largest stack considered for a base type.
+    pub fn limit(mut self, name: impl Into<String>, max_stack: u8) -> Stacks {
+        self.limits.insert(name.into(), max_stack);
+        self
+    }
+
+    /// The largest stack considered for a base type.
+    fn max(&self, price: &Price) -> u8 {
+        self.limits
+            .get(&price.name)
+            .copied()
+            .or_else(|| stack_size(&price.name))
+            .unwrap_or(self.max_stack)
+    }
+
+    /// The smallest stack of a base type that reaches each tier it can reach.
+    pub fn breakpoints(&self, price: &Price) -> BTreeMap<usize, u8> {
+        let max = self.max(price);
+        self.tiers.breakpoints(&price.name, max, |stack| {
+            price.chaos_value * f64::from(stack)
+        })
+    }
+
+    /// The value of each stack tiered, keyed by the [stack_key] of the base type.
+    pub fn tiered(&self, prices: &[Price]) -> Vec<(String, f64)> {
+        let mut tiered = vec![];
+        for price in prices {
+            for stack in 1..=self.max(price).max(1) {
+                let value = price.chaos_value * f64::from(stack);
+                tiered.push((stack_key(&price.name, stack), value));
+            }
+        }
+        tiered
+    }
+
+    /// The blocks for the stacks, best tier first so larger stacks are matched before smaller
+    /// ones.
+    pub fn blocks(&self, prices: &[Price]) -> Vec<Block> {
+        let mut blocks = BTreeMap::<_, Vec<String>>::new();
+        for price in prices {
+            for (tier, stack) in self.breakpoints(price) {
+                blocks
+                    .entry((tier, Reverse(stack), class(&price.name)))
+                    .or_default()
+                    .push(price.name.clone());
+            }
+        }
+        blocks
+            .into_iter()
+            .map(|((tier, Reverse(stack), class), names)| {
+                let mut block = Block::show()
+                    .comment(format!("$type->currency $tier->t{}", tier))
+                    .class([class])
+                    .base_type_eq(names);
+                if stack > 1 {
+                    block = block.stack_size_ge(stack);
+                }
+                match self.styles.get(tier) {
+                    Some(style) => style.apply(block),
+                    None => block,
+                }
+            })
+            .collect()
+    }
+}
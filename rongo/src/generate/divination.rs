@@ -0,0 +1,153 @@
+//! Divination cards, tiered by the value of the stack that dropped.
+//!
+//! A card worth little alone can be worth picking up in a stack, so each card gets a block for
+//! every stack size at which it reaches a better tier.
+use crate::style::Style;
+use crate::tier::{stack_key, TierList};
+use libfilter::block::Block;
+use libninja::Price;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, PartialEq)]
+/// Generates blocks for divination cards.
+///
+/// # Example
+/// ```
+/// # use rongo::generate::divination::Cards;
+/// # use rongo::style::Style;
+/// # use rongo::tier::TierList;
+/// # use libninja::Price;
+/// let prices = [Price {
+///     name: String::from("The Gambler"),
+///     chaos_value: 0.5,
+///     stack_size: Some(5),
+///     ..Price::default()
+/// }];
+/// let blocks = Cards::new(TierList::new(vec![10.0, 2.0]))
+///     .styles(vec![Style::plain(); 3])
+///     .set_fraction(None)
+///     .blocks(&prices);
+/// # assert_eq!(blocks.len(), 2);
+/// # assert_eq!(blocks[0].to_string(), "Show # $type->divination $tier->t1\n\tClass \"Divination Cards\"\n\tBaseType == \"The Gambler\"\n\tStackSize >= 4");
+/// # assert_eq!(blocks[1].to_string(), "Show # $type->divination $tier->t2\n\tClass \"Divination Cards\"\n\tBaseType == \"The Gambler\"");
+/// ```
+pub struct Cards {
+    /// The value thresholds of the tiers.
+    pub tiers: TierList,
+
+    /// The style of each tier.
+    pub styles: Vec<Style>,
+
+    /// The fraction of a full set from which a stack is valued as the full set, since the rest
+    /// is within reach. `None` values stacks by their cards alone.
+    pub set_fraction: Option<f64>,
+}
+
+impl Cards {
+    /// Tiers cards styled with [Style::ladder], valuing stacks of half a set or more as a full set.
+    pub fn new(tiers: TierList) -> Cards {
+        let styles = Style::ladder(tiers.thresholds.len() + 1);
+        Cards {
+            tiers,
+            styles,
+            set_fraction: Some(0.5),
+        }
+    }
+
+    /// Sets the style of each tier.
+    pub fn styles(mut self, styles: Vec<Style>) -> Cards {
+        self.styles = styles;
+        self
+    }
+
+    /// Sets the fraction of a full set from which a stack is valued as the full set.
+    pub fn set_fraction(mut self, fraction: Option<f64>) -> Cards {
+        self.set_fraction = fraction;
+        self
+    }
+
+    /// The value of a stack of a card.
+    ///
+    /// # Example
+    /// ```
+    /// # use rongo::generate::divination::Cards;
+    /// # use rongo::tier::TierList;
+    /// # use libninja::Price;
+    /// let doctor = Price {
+    ///     name: String::from("The Doctor"),
+    ///     chaos_value: 1000.0,
+    ///     stack_size: Some(8),
+    ///     ..Price::default()
+    /// };
+    /// let cards = Cards::new(TierList::new(vec![]));
+    /// # assert_eq!(cards.value(&doctor, 1), 1000.0);
+    /// # assert_eq!(cards.value(&doctor, 3), 3000.0);
+    /// # assert_eq!(cards.value(&doctor, 4), 8000.0);
+    /// ```
+    pub fn value(&self, price: &Price, stack: u16) -> f64 {
+        let set = price.stack_size.unwrap_or(1).max(1);
+        let near_set = self
+            .set_fraction
+            .is_some_and(|fraction| f64::from(stack) >= fraction * f64::from(set));
+        if near_set {
+            price.chaos_value * f64::from(set.max(stack))
+        } else {
+            price.chaos_value * f64::from(stack)
+        }
+    }
+
+    /// The smallest stack of a card that reaches each tier it can reach within a full set.
+    pub fn breakpoints(&self, price: &Price) -> BTreeMap<usize, u8> {
+        self.tiers
+            .breakpoints(&price.name, Cards::set(price), |stack| {
+                self.value(price, u16::from(stack))
+            })
+    }
+
+    /// The value of each stack tiered, keyed by the [stack_key] of the card.
+    pub fn tiered(&self, prices: &[Price]) -> Vec<(String, f64)> {
+        let mut tiered = vec![];
+        for price in prices {
+            for stack in 1..=Cards::set(price) {
+                let value = self.value(price, u16::from(stack));
+                tiered.push((stack_key(&price.name, stack), value));
+            }
+        }
+        tiered
+    }
+
+    /// The number of cards in a full set, as far as a stack can be matched.
+    fn set(price: &Price) -> u8 {
+        price.stack_size.unwrap_or(1).clamp(1, u16::from(u8::MAX)) as u8
+    }
+
+    /// The blocks for the cards, best tier first so larger stacks are matched before smaller ones.
+    pub fn blocks(&self, prices: &[Price]) -> Vec<Block> {
+        let mut blocks = BTreeMap::<_, Vec<String>>::new();
+        for price in prices {
+            for (tier, stack) in self.breakpoints(price) {
+                blocks
+                    .entry((tier, Reverse(stack)))
+                    .or_default()
+                    .push(price.name.clone());
+            }
+        }
+        blocks
+            .into_iter()
+            .map(|((tier, Reverse(stack)), names)| {
+                let mut block = Block::show()
+                    .comment(format!("$type->divination $tier->t{}", tier))
+                    .class(["Divination Cards"])
+                    .base_type_eq(names);
+                if stack > 1 {
+                    block = block.stack_size_ge(stack);
+                }
+                match self.styles.get(tier) {
+                    Some(style) => style.apply(block),
+                    None => block,
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,214 @@
+//! Skill gems, tiered by level, quality, corruption and alternate quality.
+//!
+//! poe.ninja prices each variant of a gem separately, so each priced variant gets a block that
+//! matches it, ordered so the most demanding variants are matched first.
+use crate::style::Style;
+use crate::tier::{label, TierList};
+use libfilter::block::Block;
+use libfilter::GemQuality;
+use libninja::Price;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The alternate qualities poe.ninja writes in front of gem names.
+const ALTERNATE_QUALITIES: [GemQuality; 3] = [
+    GemQuality::Anomalous,
+    GemQuality::Divergent,
+    GemQuality::Phantasmal,
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// One priced variant of a gem, in terms a filter can match.
+///
+/// # Example
+/// ```
+/// # use rongo::generate::gem::Variant;
+/// # use libfilter::GemQuality;
+/// # use libninja::Price;
+/// let price = Price {
+///     name: String::from("Anomalous Arc"),
+///     gem_level: Some(20),
+///     gem_quality: Some(20),
+///     ..Price::default()
+/// };
+/// let variant = Variant::of(&price);
+/// # assert_eq!(variant.base_type, "Arc");
+/// # assert_eq!(variant.quality_type, Some(GemQuality::Anomalous));
+/// # assert_eq!((variant.level, variant.quality, variant.corrupted), (20, 20, false));
+/// ```
+pub struct Variant {
+    /// The base type of the gem. Transfigured gems have their own base type.
+    pub base_type: String,
+
+    /// The alternate quality of the gem, if any.
+    pub quality_type: Option<GemQuality>,
+
+    /// The level of the gem.
+    pub level: u8,
+
+    /// The quality of the gem.
+    pub quality: u8,
+
+    /// Whether the gem is corrupted.
+    pub corrupted: bool,
+}
+
+impl Variant {
+    /// The variant a price is for.
+    pub fn of(price: &Price) -> Variant {
+        let (quality_type, base_type) = ALTERNATE_QUALITIES
+            .iter()
+            .find_map(|quality| {
+                price
+                    .name
+                    .strip_prefix(&format!("{} ", quality))
+                    .map(|base_type| (Some(*quality), base_type))
+            })
+            .unwrap_or((None, &price.name));
+        Variant {
+            base_type: base_type.to_string(),
+            quality_type,
+            level: price.gem_level.unwrap_or(1),
+            quality: price.gem_quality.unwrap_or(0),
+            corrupted: price.corrupted,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Generates blocks for skill gems.
+///
+/// A block matches its level and quality or higher, so an unpriced variant is matched by the
+/// nearest priced one below it. Corrupted variants come before uncorrupted ones of the same
+/// level and quality, and uncorrupted variants only match uncorrupted gems, which can still be
+/// improved. Since `BaseType` matches any part of a name, alternate quality gems and
+/// gems whose name contains another gem's name, such as `Vaal Arc` or a transfigured gem, come
+/// before the gems they would otherwise be matched as.
+///
+/// # Example
+/// ```
+/// # use rongo::generate::gem::Gems;
+/// # use rongo::style::Style;
+/// # use rongo::tier::TierList;
+/// # use libninja::Price;
+/// let gem = |level, quality, corrupted, chaos_value| Price {
+///     name: String::from("Empower Support"),
+///     gem_level: Some(level),
+///     gem_quality: Some(quality),
+///     corrupted,
+///     chaos_value,
+///     ..Price::default()
+/// };
+/// let prices = [gem(1, 0, false, 1.0), gem(4, 0, false, 50.0), gem(4, 20, true, 300.0)];
+/// let blocks = Gems::new(TierList::new(vec![100.0, 10.0]))
+///     .styles(vec![Style::plain(); 3])
+///     .blocks(&prices);
+/// # assert_eq!(blocks.len(), 3);
+/// # assert_eq!(blocks[0].to_string(), "Show # $type->gems $tier->t0\n\tClass \"Gems\"\n\tBaseType \"Empower Support\"\n\tGemLevel >= 4\n\tQuality >= 20\n\tCorrupted true");
+/// # assert_eq!(blocks[1].to_string(), "Show # $type->gems $tier->t1\n\tClass \"Gems\"\n\tBaseType \"Empower Support\"\n\tGemLevel >= 4\n\tCorrupted false");
+/// # assert_eq!(blocks[2].to_string(), "Show # $type->gems $tier->t2\n\tClass \"Gems\"\n\tBaseType \"Empower Support\"\n\tCorrupted false");
+/// ```
+pub struct Gems {
+    /// The value thresholds of the tiers.
+    pub tiers: TierList,
+
+    /// The style of each tier.
+    pub styles: Vec<Style>,
+}
+
+impl Gems {
+    /// Tiers gems styled with [Style::ladder].
+    pub fn new(tiers: TierList) -> Gems {
+        let styles = Style::ladder(tiers.thresholds.len() + 1);
+        Gems { tiers, styles }
+    }
+
+    /// Sets the style of each tier.
+    pub fn styles(mut self, styles: Vec<Style>) -> Gems {
+        self.styles = styles;
+        self
+    }
+
+    /// The value each priced item is tiered by, keyed by its [label].
+    pub fn tiered(&self, prices: &[Price]) -> Vec<(String, f64)> {
+        prices
+            .iter()
+            .map(|price| (label(price), price.chaos_value))
+            .collect()
+    }
+
+    /// The blocks for the gems, most demanding variants first.
+    pub fn blocks(&self, prices: &[Price]) -> Vec<Block> {
+        let variants = prices
+            .iter()
+            .map(|price| {
+                let tier = self.tiers.tier_of(&label(price), price.chaos_value);
+                (Variant::of(price), tier)
+            })
+            .collect::<Vec<_>>();
+        let names = variants
+            .iter()
+            .map(|(variant, _)| variant.base_type.as_str())
+            .collect::<BTreeSet<_>>();
+        let shadowing = names
+            .iter()
+            .filter(|name| {
+                names
+                    .iter()
+                    .any(|other| other != *name && name.contains(other))
+            })
+            .collect::<BTreeSet<_>>();
+        let mut blocks = BTreeMap::<_, Vec<String>>::new();
+        for (variant, tier) in &variants {
+            let shadows = shadowing.contains(&variant.base_type.as_str());
+            blocks
+                .entry((
+                    Reverse(variant.quality_type),
+                    Reverse(shadows),
+                    Reverse(variant.level),
+                    Reverse(variant.quality),
+                    Reverse(variant.corrupted),
+                    *tier,
+                ))
+                .or_default()
+                .push(variant.base_type.clone());
+        }
+        blocks
+            .into_iter()
+            .map(
+                |(
+                    (
+                        Reverse(quality_type),
+                        _,
+                        Reverse(level),
+                        Reverse(quality),
+                        Reverse(corrupted),
+                        tier,
+                    ),
+                    mut base_types,
+                )| {
+                    base_types.sort();
+                    base_types.dedup();
+                    let mut block = Block::show()
+                        .comment(format!("$type->gems $tier->t{}", tier))
+                        .class(["Gems"])
+                        .base_type(base_types);
+                    if let Some(quality_type) = quality_type {
+                        block = block.gem_quality_type(quality_type);
+                    }
+                    if level > 1 {
+                        block = block.gem_level_ge(level);
+                    }
+                    if quality > 0 {
+                        block = block.quality_ge(quality);
+                    }
+                    block = block.corrupted(corrupted);
+                    match self.styles.get(tier) {
+                        Some(style) => style.apply(block),
+                        None => block,
+                    }
+                },
+            )
+            .collect()
+    }
+}
@@ -0,0 +1,228 @@
+//! Items worth picking up while leveling through the campaign.
+//!
+//! Every block is limited by `AreaLevel`, so the endgame filter takes over as the character
+//! outgrows them. Each block stays shown for a few more area levels in a faded style before
+//! it stops applying, so upgrades do not vanish the moment a zone's level ticks over.
+use crate::style::Style;
+use libfilter::block::Block;
+use libfilter::{Operator, Rarity};
+
+/// The life and mana flask sizes, with the area level they start dropping at, smallest first.
+pub const FLASKS: [(&str, u8); 12] = [
+    ("Small", 1),
+    ("Medium", 3),
+    ("Large", 6),
+    ("Greater", 12),
+    ("Grand", 18),
+    ("Giant", 24),
+    ("Colossal", 30),
+    ("Sacred", 36),
+    ("Hallowed", 42),
+    ("Sanctified", 50),
+    ("Divine", 60),
+    ("Eternal", 65),
+];
+
+/// The movement speed prefixes of boots, fastest first.
+pub const MOVEMENT_SPEED_MODS: [&str; 6] = [
+    "Hellion's",
+    "Cheetah's",
+    "Gazelle's",
+    "Stallion's",
+    "Sprinter's",
+    "Runner's",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+/// Generates blocks for leveling.
+///
+/// # Example
+/// ```
+/// # use rongo::generate::leveling::Leveling;
+/// # use rongo::style::Style;
+/// let blocks = Leveling::new()
+///     .links(vec![(4, 40)])
+///     .socket_group("RRG")
+///     .movement_mods(vec![String::from("Runner's")])
+///     .style(Style::plain())
+///     .faded(Style::plain())
+///     .blocks();
+/// # let blocks = blocks.iter().map(|block| block.to_string()).collect::<Vec<_>>();
+/// # assert_eq!(blocks[0], "Show # $type->leveling $rule->sockets\n\tAreaLevel < 68\n\tRarity <= Rare\n\tSocketGroup >= 3RRG");
+/// # assert_eq!(blocks[1], "Show # $type->leveling $rule->links\n\tAreaLevel < 40\n\tRarity <= Rare\n\tLinkedSockets >= 4");
+/// # assert_eq!(blocks[2], "Show # $type->leveling $rule->boots\n\tAreaLevel < 68\n\tRarity <= Rare\n\tClass \"Boots\"\n\tHasExplicitMod \"Runner's\"");
+/// # assert_eq!(blocks[3], "Show # $type->leveling $rule->flasks\n\tAreaLevel < 9\n\tClass \"Life Flasks\" \"Mana Flasks\"\n\tBaseType \"Small Life Flask\" \"Small Mana Flask\"");
+/// # assert_eq!(blocks.len(), 2 * 15);
+/// # assert_eq!(blocks[15], "Show # $type->leveling $rule->sockets $faded\n\tAreaLevel < 73\n\tRarity <= Rare\n\tSocketGroup >= 3RRG");
+/// ```
+pub struct Leveling {
+    /// The area level at which leveling ends and every block stops applying.
+    pub until: u8,
+
+    /// Items with at least a number of linked sockets, shown below an area level.
+    pub links: Vec<(u8, u8)>,
+
+    /// Socket colors the build needs linked, such as `RRG`.
+    pub socket_groups: Vec<String>,
+
+    /// The explicit mods that make boots worth picking up.
+    pub movement_mods: Vec<String>,
+
+    /// How many area levels a flask size stays shown after the next size starts dropping.
+    pub flask_grace: u8,
+
+    /// How many area levels a block stays shown in the faded style after it ends.
+    pub fade: u8,
+
+    /// The style of items while they are upgrades.
+    pub style: Style,
+
+    /// The style of items while they fade out.
+    pub faded: Style,
+}
+
+impl Leveling {
+    /// Shows three links until area level 25, four links until 55 and five links until maps,
+    /// boots with any movement speed prefix, and each flask size until six area levels after
+    /// the next one starts dropping, fading over five area levels.
+    pub fn new() -> Leveling {
+        Leveling {
+            until: 68,
+            links: vec![(5, 68), (4, 55), (3, 25)],
+            socket_groups: vec![],
+            movement_mods: MOVEMENT_SPEED_MODS.iter().map(|m| m.to_string()).collect(),
+            flask_grace: 6,
+            fade: 5,
+            style: Style {
+                font_size: Some(40),
+                ..Style::plain()
+            },
+            faded: Style {
+                font_size: Some(30),
+                ..Style::plain()
+            },
+        }
+    }
+
+    /// Sets the area level at which leveling ends.
+    pub fn until(mut self, area_level: u8) -> Leveling {
+        self.until = area_level;
+        self
+    }
+
+    /// Sets the linked socket counts shown and the area level each stops at.
+    pub fn links(mut self, links: Vec<(u8, u8)>) -> Leveling {
+        self.links = links;
+        self
+    }
+
+    /// Adds socket colors the build needs linked.
+    pub fn socket_group(mut self, colors: impl Into<String>) -> Leveling {
+        self.socket_groups.push(colors.into());
+        self
+    }
+
+    /// Sets the explicit mods that make boots worth picking up.
+    pub fn movement_mods(mut self, mods: Vec<String>) -> Leveling {
+        self.movement_mods = mods;
+        self
+    }
+
+    /// Sets how long a flask size stays shown after the next size starts dropping.
+    pub fn flask_grace(mut self, area_levels: u8) -> Leveling {
+        self.flask_grace = area_levels;
+        self
+    }
+
+    /// Sets how many area levels a block fades over.
+    pub fn fade(mut self, area_levels: u8) -> Leveling {
+        self.fade = area_levels;
+        self
+    }
+
+    /// Sets the style of items while they are upgrades.
+    pub fn style(mut self, style: Style) -> Leveling {
+        self.style = style;
+        self
+    }
+
+    /// Sets the style of items while they fade out.
+    pub fn faded(mut self, style: Style) -> Leveling {
+        self.faded = style;
+        self
+    }
+
+    /// What each block matches, its tag and the area level it ends at, most specific first.
+    fn rules(&self) -> Vec<(u8, &'static str, Block)> {
+        let mut rules = vec![];
+        for colors in &self.socket_groups {
+            let block = Block::show()
+                .rarity(Operator::LessThanOrEqual, Rarity::Rare)
+                .socket_group(
+                    Operator::GreaterThanOrEqual,
+                    colors.len() as u8,
+                    colors.as_str(),
+                );
+            rules.push((self.until, "sockets", block));
+        }
+        let mut links = self.links.clone();
+        links.sort_by(|a, b| b.cmp(a));
+        for (count, until) in links {
+            let block = Block::show()
+                .rarity(Operator::LessThanOrEqual, Rarity::Rare)
+                .linked_sockets_ge(count);
+            rules.push((until.min(self.until), "links", block));
+        }
+        for name in &self.movement_mods {
+            let block = Block::show()
+                .rarity(Operator::LessThanOrEqual, Rarity::Rare)
+                .class(["Boots"])
+                .has_explicit_mod(name.as_str());
+            rules.push((self.until, "boots", block));
+        }
+        for (i, (size, _)) in FLASKS.iter().enumerate() {
+            let until = match FLASKS.get(i + 1) {
+                Some((_, next)) => next.saturating_add(self.flask_grace).min(self.until),
+                None => self.until,
+            };
+            let block = Block::show()
+                .class(["Life Flasks", "Mana Flasks"])
+                .base_type([
+                    format!("{} Life Flask", size),
+                    format!("{} Mana Flask", size),
+                ]);
+            rules.push((until, "flasks", block));
+        }
+        rules
+    }
+
+    /// The blocks for leveling, all upgrades first and then everything fading out.
+    pub fn blocks(&self) -> Vec<Block> {
+        let rules = self.rules();
+        let shown = rules.iter().map(|(until, tag, rule)| {
+            let block = Block::show()
+                .comment(format!("$type->leveling $rule->{}", tag))
+                .area_level(Operator::LessThan, *until);
+            self.style.apply(with_lines(block, rule))
+        });
+        let faded = rules.iter().map(|(until, tag, rule)| {
+            let block = Block::show()
+                .comment(format!("$type->leveling $rule->{} $faded", tag))
+                .area_level(Operator::LessThan, until.saturating_add(self.fade));
+            self.faded.apply(with_lines(block, rule))
+        });
+        shown.chain(faded).collect()
+    }
+}
+
+impl Default for Leveling {
+    fn default() -> Leveling {
+        Leveling::new()
+    }
+}
+
+/// Adds the lines of a rule to a block.
+fn with_lines(mut block: Block, rule: &Block) -> Block {
+    block.lines.extend(rule.lines.iter().cloned());
+    block
+}
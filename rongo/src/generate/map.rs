@@ -0,0 +1,233 @@
+//! Maps, tiered by value with floors by atlas tier, and hidden in high level areas.
+//!
+//! Regular, blighted, blight-ravaged, shaped, elder and unique maps share base types, so each
+//! kind of map is told apart by its own conditions, and regular maps rule out every other kind.
+//! Blocks are ordered by tier, and blocks hiding low maps come just before the lowest tier, so a
+//! valuable low map is still shown.
+use crate::style::Style;
+use crate::tier::{label, TierList};
+use libfilter::block::Block;
+use libfilter::{Operator, Rarity};
+use libninja::Price;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// What kind of map an item is.
+pub enum MapKind {
+    /// A blight-ravaged map.
+    UberBlighted,
+    /// A blighted map.
+    Blighted,
+    /// A unique map.
+    Unique,
+    /// A map without special properties.
+    Regular,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// An influence a map can carry.
+pub enum MapInfluence {
+    /// The map is shaped.
+    Shaped,
+    /// The map is influenced by the Elder.
+    Elder,
+}
+
+impl MapKind {
+    /// The kind and base type of a priced map.
+    ///
+    /// # Example
+    /// ```
+    /// # use rongo::generate::map::MapKind;
+    /// # use libninja::Price;
+    /// let map = |name: &str, base_type: &str| Price {
+    ///     name: String::from(name),
+    ///     base_type: Some(String::from(base_type)),
+    ///     ..Price::default()
+    /// };
+    /// # assert_eq!(MapKind::of(&map("Blighted Atoll Map", "Atoll Map")), (MapKind::Blighted, String::from("Atoll Map")));
+    /// # assert_eq!(MapKind::of(&map("Blight-ravaged Atoll Map", "Atoll Map")).0, MapKind::UberBlighted);
+    /// # assert_eq!(MapKind::of(&map("Maelström of Chaos", "Atoll Map")).0, MapKind::Unique);
+    /// # assert_eq!(MapKind::of(&map("Atoll Map", "Atoll Map")).0, MapKind::Regular);
+    /// ```
+    pub fn of(price: &Price) -> (MapKind, String) {
+        let base_type = |name: &str| match &price.base_type {
+            Some(base_type) if !base_type.is_empty() => base_type.clone(),
+            _ => name.to_string(),
+        };
+        if let Some(name) = price.name.strip_prefix("Blight-ravaged ") {
+            (MapKind::UberBlighted, base_type(name))
+        } else if let Some(name) = price.name.strip_prefix("Blighted ") {
+            (MapKind::Blighted, base_type(name))
+        } else if price.name.ends_with(" Map") {
+            (MapKind::Regular, base_type(&price.name))
+        } else {
+            (MapKind::Unique, base_type(&price.name))
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Generates blocks for maps.
+///
+/// # Example
+/// ```
+/// # use rongo::generate::map::Maps;
+/// # use rongo::style::Style;
+/// # use rongo::tier::TierList;
+/// # use libninja::Price;
+/// let map = |name: &str, map_tier, chaos_value| Price {
+///     name: String::from(name),
+///     base_type: Some(String::from("Atoll Map")),
+///     map_tier: Some(map_tier),
+///     chaos_value,
+///     ..Price::default()
+/// };
+/// let prices = [map("Atoll Map", 5, 1.0), map("Blighted Atoll Map", 5, 8.0), map("Blight-ravaged Atoll Map", 16, 60.0)];
+/// let blocks = Maps::new(TierList::new(vec![50.0, 5.0]))
+///     .styles(vec![Style::plain(); 3])
+///     .floor(16, 1)
+///     .hide_below(78, 14)
+///     .blocks(&prices);
+/// # let blocks = blocks.iter().map(|block| block.to_string()).collect::<Vec<_>>();
+/// # assert_eq!(blocks, [
+/// #     "Show # $type->maps $tier->t0\n\tClass \"Maps\"\n\tBaseType == \"Atoll Map\"\n\tUberBlightedMap true",
+/// #     "Show # $type->maps $tier->t1\n\tClass \"Maps\"\n\tBaseType == \"Atoll Map\"\n\tRarity <= Rare\n\tBlightedMap true\n\tUberBlightedMap false",
+/// #     "Show # $type->maps $tier->t1\n\tClass \"Maps\"\n\tMapTier >= 16",
+/// #     "Hide # $type->maps $hide\n\tClass \"Maps\"\n\tAreaLevel >= 78\n\tMapTier < 14",
+/// #     "Show # $type->maps $tier->t2\n\tClass \"Maps\"\n\tBaseType == \"Atoll Map\"\n\tRarity <= Rare\n\tBlightedMap false\n\tUberBlightedMap false\n\tShapedMap false\n\tElderMap false",
+/// # ]);
+/// ```
+pub struct Maps {
+    /// The value thresholds of the tiers.
+    pub tiers: TierList,
+
+    /// The style of each tier.
+    pub styles: Vec<Style>,
+
+    /// The worst tier of maps of at least an atlas tier, however little they are worth.
+    pub floors: BTreeMap<u8, usize>,
+
+    /// Maps below an atlas tier hidden in areas of at least an area level.
+    pub hide: Vec<(u8, u8)>,
+}
+
+impl Maps {
+    /// Tiers maps by value alone, styled with [Style::ladder], hiding none.
+    pub fn new(tiers: TierList) -> Maps {
+        let styles = Style::ladder(tiers.thresholds.len() + 1);
+        Maps {
+            tiers,
+            styles,
+            floors: BTreeMap::new(),
+            hide: vec![],
+        }
+    }
+
+    /// Sets the style of each tier.
+    pub fn styles(mut self, styles: Vec<Style>) -> Maps {
+        self.styles = styles;
+        self
+    }
+
+    /// Puts maps of at least an atlas tier in at least a tier.
+    pub fn floor(mut self, map_tier: u8, tier: usize) -> Maps {
+        self.floors.insert(map_tier, tier);
+        self
+    }
+
+    /// Hides maps below an atlas tier in areas of at least an area level.
+    pub fn hide_below(mut self, area_level: u8, map_tier: u8) -> Maps {
+        self.hide.push((area_level, map_tier));
+        self
+    }
+
+    /// The influence of a priced map, if its variant names one.
+    fn influence(price: &Price) -> Option<MapInfluence> {
+        match price.variant.as_deref() {
+            Some("Shaped") | Some("Shaper") => Some(MapInfluence::Shaped),
+            Some("Elder") => Some(MapInfluence::Elder),
+            _ => None,
+        }
+    }
+
+    /// The value each priced item is tiered by, keyed by its [label].
+    pub fn tiered(&self, prices: &[Price]) -> Vec<(String, f64)> {
+        prices
+            .iter()
+            .map(|price| (label(price), price.chaos_value))
+            .collect()
+    }
+
+    /// The blocks for the maps.
+    pub fn blocks(&self, prices: &[Price]) -> Vec<Block> {
+        let mut groups = BTreeMap::<_, Vec<String>>::new();
+        for price in prices {
+            let (kind, base_type) = MapKind::of(price);
+            let influence = Maps::influence(price);
+            let tier = self.tiers.tier_of(&label(price), price.chaos_value);
+            groups
+                .entry((tier, Reverse(influence), kind))
+                .or_default()
+                .push(base_type);
+        }
+        // Within a tier, hiding comes first, then priced maps, then floors by atlas tier.
+        let mut blocks = vec![];
+        for ((tier, Reverse(influence), kind), mut base_types) in groups {
+            base_types.sort();
+            base_types.dedup();
+            let mut block = Block::show()
+                .comment(format!("$type->maps $tier->t{}", tier))
+                .class(["Maps"])
+                .base_type_eq(base_types);
+            block = match kind {
+                MapKind::UberBlighted => block.uber_blighted_map(true),
+                MapKind::Blighted => block
+                    .rarity(Operator::LessThanOrEqual, Rarity::Rare)
+                    .blighted_map(true)
+                    .uber_blighted_map(false),
+                MapKind::Unique => block.rarity(Operator::Equal, Rarity::Unique),
+                MapKind::Regular => block
+                    .rarity(Operator::LessThanOrEqual, Rarity::Rare)
+                    .blighted_map(false)
+                    .uber_blighted_map(false),
+            };
+            block = match (influence, kind) {
+                (Some(MapInfluence::Shaped), _) => block.shaped_map(true),
+                (Some(MapInfluence::Elder), _) => block.elder_map(true),
+                (None, MapKind::Regular) => block.shaped_map(false).elder_map(false),
+                (None, _) => block,
+            };
+            blocks.push((tier, 1, self.style(tier, block)));
+        }
+        for (map_tier, tier) in &self.floors {
+            let block = Block::show()
+                .comment(format!("$type->maps $tier->t{}", tier))
+                .class(["Maps"])
+                .map_tier_ge(*map_tier);
+            blocks.push((*tier, 2, self.style(*tier, block)));
+        }
+        let mut hide = self.hide.clone();
+        hide.sort_by_key(|(area_level, _)| Reverse(*area_level));
+        let lowest = self.tiers.thresholds.len();
+        for (area_level, map_tier) in hide {
+            let block = Block::hide()
+                .comment("$type->maps $hide")
+                .class(["Maps"])
+                .area_level_ge(area_level)
+                .map_tier(Operator::LessThan, map_tier);
+            blocks.push((lowest, 0, block));
+        }
+        blocks.sort_by_key(|(tier, order, _)| (*tier, *order));
+        blocks.into_iter().map(|(_, _, block)| block).collect()
+    }
+
+    /// Applies the style of a tier to a block.
+    fn style(&self, tier: usize, block: Block) -> Block {
+        match self.styles.get(tier) {
+            Some(style) => style.apply(block),
+            None => block,
+        }
+    }
+}
@@ -0,0 +1,263 @@
+//! The Chaos Orb and Regal Orb vendor recipes, shown slot by slot.
+//!
+//! A full set is a helmet, body armour, gloves, boots, belt, amulet, two rings and two
+//! one-handed weapons. The number of items already collected for each slot is read from a
+//! file, and slots that have enough for the wanted number of sets are hidden, so the filter
+//! can be regenerated as sets fill.
+use crate::style::Style;
+use libfilter::block::Block;
+use libfilter::{Operator, Rarity};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Error;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+/// A part of a recipe set.
+pub enum Slot {
+    /// A helmet.
+    Helmet,
+    /// A body armour.
+    BodyArmour,
+    /// A pair of gloves.
+    Gloves,
+    /// A pair of boots.
+    Boots,
+    /// A belt.
+    Belt,
+    /// An amulet.
+    Amulet,
+    /// A ring, two to a set.
+    Ring,
+    /// A one-handed weapon or shield, two to a set.
+    Weapon,
+}
+
+impl Slot {
+    /// Every slot of a set.
+    pub const ALL: [Slot; 8] = [
+        Slot::Helmet,
+        Slot::BodyArmour,
+        Slot::Gloves,
+        Slot::Boots,
+        Slot::Belt,
+        Slot::Amulet,
+        Slot::Ring,
+        Slot::Weapon,
+    ];
+
+    /// The item classes that fill the slot.
+    pub fn classes(self) -> &'static [&'static str] {
+        match self {
+            Slot::Helmet => &["Helmets"],
+            Slot::BodyArmour => &["Body Armours"],
+            Slot::Gloves => &["Gloves"],
+            Slot::Boots => &["Boots"],
+            Slot::Belt => &["Belts"],
+            Slot::Amulet => &["Amulets"],
+            Slot::Ring => &["Rings"],
+            Slot::Weapon => &[
+                "Claws",
+                "Daggers",
+                "One Hand Axes",
+                "One Hand Maces",
+                "One Hand Swords",
+                "Rune Daggers",
+                "Sceptres",
+                "Shields",
+                "Thrusting One Hand Swords",
+                "Wands",
+            ],
+        }
+    }
+
+    /// How many items of the slot a set takes.
+    pub fn per_set(self) -> u32 {
+        match self {
+            Slot::Ring | Slot::Weapon => 2,
+            _ => 1,
+        }
+    }
+
+    /// The tag of the slot in block comments.
+    fn tag(self) -> &'static str {
+        match self {
+            Slot::Helmet => "helmet",
+            Slot::BodyArmour => "body",
+            Slot::Gloves => "gloves",
+            Slot::Boots => "boots",
+            Slot::Belt => "belt",
+            Slot::Amulet => "amulet",
+            Slot::Ring => "ring",
+            Slot::Weapon => "weapon",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Which recipe the items are collected for.
+pub enum RecipeKind {
+    /// Rares of item level 60 to 74, sold for Chaos Orbs.
+    Chaos,
+    /// Rares of item level 75 and above, sold for Regal Orbs.
+    Regal,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+/// The number of items already collected for each slot, such as `{"Ring": 3, "Helmet": 1}`.
+///
+/// Slots that are left out have none.
+///
+/// # Example
+/// ```
+/// # use rongo::generate::recipe::{Collected, Slot};
+/// let collected: Collected = serde_json::from_str(r#"{"Ring": 3, "BodyArmour": 1}"#).unwrap();
+/// # assert_eq!(collected.get(Slot::Ring), 3);
+/// # assert_eq!(collected.get(Slot::Helmet), 0);
+/// ```
+pub struct Collected {
+    /// The number of items of each slot.
+    pub counts: BTreeMap<Slot, u32>,
+}
+
+impl Collected {
+    /// Reads the counts from a file, or no counts if there is no file.
+    pub fn load(path: &str) -> Result<Collected, Error> {
+        match fs::read_to_string(path) {
+            Ok(text) => Ok(serde_json::from_str(&text)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Collected::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Writes the counts to a file.
+    pub fn save(&self, path: &str) -> Result<(), Error> {
+        fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+
+    /// The number of items collected for a slot.
+    pub fn get(&self, slot: Slot) -> u32 {
+        self.counts.get(&slot).copied().unwrap_or(0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Generates blocks for a vendor recipe.
+///
+/// # Example
+/// ```
+/// # use rongo::generate::recipe::{Collected, Recipe, RecipeKind, Slot};
+/// # use rongo::style::Style;
+/// let mut collected = Collected::default();
+/// collected.counts.insert(Slot::Ring, 4);
+/// let blocks = Recipe::new(RecipeKind::Chaos)
+///     .sets(2)
+///     .style(Style::plain())
+///     .blocks(&collected);
+/// # let blocks = blocks.iter().map(|block| block.to_string()).collect::<Vec<_>>();
+/// # assert_eq!(blocks[0], "Show # $type->recipe $slot->helmet\n\tRarity == Rare\n\tIdentified false\n\tItemLevel >= 60\n\tItemLevel <= 74\n\tClass \"Helmets\"");
+/// # assert_eq!(blocks[6], "Hide # $type->recipe $slot->ring $full\n\tRarity == Rare\n\tIdentified false\n\tItemLevel >= 60\n\tItemLevel <= 74\n\tClass \"Rings\"");
+/// # assert_eq!(blocks[7], "Show # $type->recipe $slot->weapon\n\tRarity == Rare\n\tIdentified false\n\tItemLevel >= 60\n\tItemLevel <= 74\n\tClass \"Claws\" \"Daggers\" \"One Hand Axes\" \"One Hand Maces\" \"One Hand Swords\" \"Rune Daggers\" \"Sceptres\" \"Shields\" \"Thrusting One Hand Swords\" \"Wands\"\n\tWidth <= 2\n\tHeight <= 3");
+/// ```
+pub struct Recipe {
+    /// Which recipe the items are collected for.
+    pub kind: RecipeKind,
+
+    /// The number of sets to collect before a slot is hidden.
+    pub sets: u32,
+
+    /// Whether only unidentified items are shown, which sell for twice as much.
+    pub unidentified: bool,
+
+    /// The largest width and height of items of each slot, so sets fit in the inventory.
+    pub sizes: BTreeMap<Slot, (u8, u8)>,
+
+    /// The style of slots that still need items.
+    pub style: Style,
+
+    /// The style of slots that have enough items.
+    pub full: Style,
+}
+
+impl Recipe {
+    /// Collects four sets of unidentified items, with weapons of at most 2 by 3, hiding full
+    /// slots.
+    pub fn new(kind: RecipeKind) -> Recipe {
+        Recipe {
+            kind,
+            sets: 4,
+            unidentified: true,
+            sizes: BTreeMap::from([(Slot::Weapon, (2, 3))]),
+            style: Style::plain(),
+            full: Style::hidden(),
+        }
+    }
+
+    /// Sets the number of sets to collect.
+    pub fn sets(mut self, sets: u32) -> Recipe {
+        self.sets = sets;
+        self
+    }
+
+    /// Sets whether only unidentified items are shown.
+    pub fn unidentified(mut self, unidentified: bool) -> Recipe {
+        self.unidentified = unidentified;
+        self
+    }
+
+    /// Sets the largest width and height of items of a slot.
+    pub fn size(mut self, slot: Slot, width: u8, height: u8) -> Recipe {
+        self.sizes.insert(slot, (width, height));
+        self
+    }
+
+    /// Sets the style of slots that still need items.
+    pub fn style(mut self, style: Style) -> Recipe {
+        self.style = style;
+        self
+    }
+
+    /// Sets the style of slots that have enough items.
+    pub fn full(mut self, style: Style) -> Recipe {
+        self.full = style;
+        self
+    }
+
+    /// A block for each slot, styled by whether it still needs items.
+    pub fn blocks(&self, collected: &Collected) -> Vec<Block> {
+        Slot::ALL
+            .iter()
+            .map(|&slot| {
+                let full = collected.get(slot) >= self.sets * slot.per_set();
+                let mut comment = format!("$type->recipe $slot->{}", slot.tag());
+                if full {
+                    comment.push_str(" $full");
+                }
+                let mut block = Block::show()
+                    .comment(comment)
+                    .rarity(Operator::Equal, Rarity::Rare);
+                if self.unidentified {
+                    block = block.identified(false);
+                }
+                block = match self.kind {
+                    RecipeKind::Chaos => block
+                        .item_level_ge(60)
+                        .item_level(Operator::LessThanOrEqual, 74),
+                    RecipeKind::Regal => block.item_level_ge(75),
+                };
+                block = block.class(slot.classes().iter().copied());
+                if let Some((width, height)) = self.sizes.get(&slot) {
+                    block = block
+                        .width(Operator::LessThanOrEqual, *width)
+                        .height(Operator::LessThanOrEqual, *height);
+                }
+                if full {
+                    self.full.apply(block)
+                } else {
+                    self.style.apply(block)
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,311 @@
+//! Unique items, tiered by base type.
+//!
+//! A filter can only tell uniques apart by their base type, so every unique on a base shares
+//! one tier. A [Strategy] decides what a base is worth from the uniques that drop on it.
+use crate::style::Style;
+use crate::tier::TierList;
+use libfilter::block::Block;
+use libfilter::{Operator, Rarity};
+use libninja::Price;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, PartialEq)]
+/// How the value of a base type is worked out from the uniques that share it.
+pub enum Strategy {
+    /// The cheapest unique, so a base is only highlighted if every unique on it is worth it.
+    Min,
+
+    /// The most valuable unique, so no unique worth picking up is missed.
+    Max,
+
+    /// The value of an average drop, weighting each unique by how often it drops.
+    Weighted,
+
+    /// The most valuable unique if it is worth at least this many Chaos Orbs, otherwise the
+    /// cheapest, so a single chase unique is enough to show its base.
+    AnyChase(f64),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+/// The uniques that share one block. Fields are `None` when they are not split on.
+pub struct Group {
+    /// The base type.
+    pub base_type: String,
+
+    /// Whether the uniques are replicas.
+    pub replica: Option<bool>,
+
+    /// Whether the uniques are corrupted.
+    pub corrupted: Option<bool>,
+
+    /// The number of linked sockets.
+    pub links: Option<u8>,
+}
+
+impl Group {
+    /// The base type with whatever else the group is split on, such as `Vaal Regalia replica 6L`.
+    pub fn key(&self) -> String {
+        let mut key = self.base_type.clone();
+        let parts = [
+            self.replica
+                .map(|replica| String::from(if replica { "replica" } else { "not replica" })),
+            self.corrupted.map(|corrupted| {
+                String::from(if corrupted {
+                    "corrupted"
+                } else {
+                    "not corrupted"
+                })
+            }),
+            self.links.map(|links| format!("{}L", links)),
+        ];
+        for part in parts.into_iter().flatten() {
+            key.push(' ');
+            key.push_str(&part);
+        }
+        key
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Generates blocks for unique items.
+///
+/// # Example
+/// ```
+/// # use rongo::generate::unique::{Strategy, Uniques};
+/// # use rongo::tier::TierList;
+/// # use libninja::Price;
+/// let unique = |name: &str, chaos_value| Price {
+///     name: String::from(name),
+///     base_type: Some(String::from("Prismatic Jewel")),
+///     chaos_value,
+///     ..Price::default()
+/// };
+/// let prices = [unique("Watcher's Eye", 900.0), unique("Sublime Vision", 1.0)];
+/// let uniques = Uniques::new(TierList::new(vec![100.0, 10.0]));
+///
+/// let blocks = uniques.clone().strategy(Strategy::AnyChase(500.0)).blocks(&prices);
+/// # assert!(blocks[0].to_string().starts_with("Show # $type->uniques $tier->t0\n\tRarity == Unique\n\tBaseType == \"Prismatic Jewel\""));
+/// let blocks = uniques.strategy(Strategy::Min).blocks(&prices);
+/// # assert!(blocks[0].to_string().starts_with("Show # $type->uniques $tier->t2\n"));
+/// ```
+pub struct Uniques {
+    /// The value thresholds of the tiers.
+    pub tiers: TierList,
+
+    /// The style of each tier.
+    pub styles: Vec<Style>,
+
+    /// How bases shared by several uniques are valued.
+    pub strategy: Strategy,
+
+    /// How often each unique drops relative to others, by name. Uniques that are not listed
+    /// weigh `1.0`. Only used by [Strategy::Weighted].
+    pub weights: BTreeMap<String, f64>,
+
+    /// Whether replicas get their own blocks.
+    pub split_replica: bool,
+
+    /// Whether corrupted uniques get their own blocks.
+    pub split_corrupted: bool,
+
+    /// Whether uniques with five or more linked sockets get their own blocks.
+    pub split_links: bool,
+}
+
+impl Uniques {
+    /// Tiers bases by the value of an average drop, styled with [Style::ladder], without splits.
+    pub fn new(tiers: TierList) -> Uniques {
+        let styles = Style::ladder(tiers.thresholds.len() + 1);
+        Uniques {
+            tiers,
+            styles,
+            strategy: Strategy::Weighted,
+            weights: BTreeMap::new(),
+            split_replica: false,
+            split_corrupted: false,
+            split_links: false,
+        }
+    }
+
+    /// Sets the style of each tier.
+    pub fn styles(mut self, styles: Vec<Style>) -> Uniques {
+        self.styles = styles;
+        self
+    }
+
+    /// Sets how bases shared by several uniques are valued.
+    pub fn strategy(mut self, strategy: Strategy) -> Uniques {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Sets how often a unique drops relative to others.
+    pub fn weight(mut self, name: impl Into<String>, weight: f64) -> Uniques {
+        self.weights.insert(name.into(), weight);
+        self
+    }
+
+    /// Sets whether replicas get their own blocks.
+    pub fn split_replica(mut self, split: bool) -> Uniques {
+        self.split_replica = split;
+        self
+    }
+
+    /// Sets whether corrupted uniques get their own blocks.
+    pub fn split_corrupted(mut self, split: bool) -> Uniques {
+        self.split_corrupted = split;
+        self
+    }
+
+    /// Sets whether uniques with five or more linked sockets get their own blocks.
+    pub fn split_links(mut self, split: bool) -> Uniques {
+        self.split_links = split;
+        self
+    }
+
+    /// The group a unique belongs to, or `None` if it is a variant that is not split on.
+    fn group(&self, price: &Price) -> Option<Group> {
+        let links = price.links.filter(|links| *links >= 5);
+        if (links.is_some() && !self.split_links) || (price.corrupted && !self.split_corrupted) {
+            return None;
+        }
+        Some(Group {
+            base_type: price.base_type.clone()?,
+            replica: self
+                .split_replica
+                .then(|| price.name.starts_with("Replica ")),
+            corrupted: self.split_corrupted.then_some(price.corrupted),
+            links,
+        })
+    }
+
+    /// The value of each group of uniques.
+    ///
+    /// # Example
+    /// ```
+    /// # use rongo::generate::unique::Uniques;
+    /// # use rongo::tier::TierList;
+    /// # use libninja::Price;
+    /// let unique = |name: &str, chaos_value| Price {
+    ///     name: String::from(name),
+    ///     base_type: Some(String::from("Leather Belt")),
+    ///     chaos_value,
+    ///     ..Price::default()
+    /// };
+    /// let prices = [unique("Headhunter", 5000.0), unique("Wurm's Molt", 1.0)];
+    /// let uniques = Uniques::new(TierList::new(vec![100.0])).weight("Headhunter", 0.001);
+    /// let values = uniques.values(&prices);
+    /// # let value = *values.values().next().unwrap();
+    /// # assert!((value - 5.994).abs() < 1e-3);
+    /// ```
+    pub fn values(&self, prices: &[Price]) -> BTreeMap<Group, f64> {
+        let mut groups = BTreeMap::<Group, Vec<&Price>>::new();
+        for price in prices {
+            if let Some(group) = self.group(price) {
+                groups.entry(group).or_default().push(price);
+            }
+        }
+        groups
+            .into_iter()
+            .map(|(group, prices)| {
+                let values = prices.iter().map(|price| price.chaos_value);
+                let min = values.clone().fold(f64::INFINITY, f64::min);
+                let max = values.fold(f64::NEG_INFINITY, f64::max);
+                let value = match self.strategy {
+                    Strategy::Min => min,
+                    Strategy::Max => max,
+                    Strategy::AnyChase(chase) if max >= chase => max,
+                    Strategy::AnyChase(_) => min,
+                    Strategy::Weighted => {
+                        let weight =
+                            |price: &&Price| self.weights.get(&price.name).copied().unwrap_or(1.0);
+                        let total = prices.iter().map(weight).sum::<f64>();
+                        if total > 0.0 {
+                            prices
+                                .iter()
+                                .map(|price| weight(price) * price.chaos_value)
+                                .sum::<f64>()
+                                / total
+                        } else {
+                            min
+                        }
+                    }
+                };
+                (group, value)
+            })
+            .collect()
+    }
+
+    /// The value each group of uniques is tiered by, keyed by [Group::key].
+    pub fn tiered(&self, prices: &[Price]) -> Vec<(String, f64)> {
+        self.values(prices)
+            .into_iter()
+            .map(|(group, value)| (group.key(), value))
+            .collect()
+    }
+
+    /// The blocks for the uniques, one per tier and split, with linked uniques first so they
+    /// are matched before their unlinked base.
+    ///
+    /// # Example
+    /// ```
+    /// # use rongo::generate::unique::Uniques;
+    /// # use rongo::style::Style;
+    /// # use rongo::tier::TierList;
+    /// # use libninja::Price;
+    /// let unique = |name: &str, links, chaos_value| Price {
+    ///     name: String::from(name),
+    ///     base_type: Some(String::from("Vaal Regalia")),
+    ///     chaos_value,
+    ///     links,
+    ///     ..Price::default()
+    /// };
+    /// let prices = [
+    ///     unique("Shavronne's Wrappings", None, 20.0),
+    ///     unique("Shavronne's Wrappings", Some(6), 150.0),
+    ///     unique("Replica Shavronne's Wrappings", None, 5.0),
+    /// ];
+    /// let blocks = Uniques::new(TierList::new(vec![100.0, 10.0]))
+    ///     .styles(vec![Style::plain(); 3])
+    ///     .split_links(true)
+    ///     .split_replica(true)
+    ///     .blocks(&prices);
+    /// # assert_eq!(blocks.len(), 3);
+    /// # assert_eq!(blocks[0].to_string(), "Show # $type->uniques $tier->t0\n\tRarity == Unique\n\tBaseType == \"Vaal Regalia\"\n\tReplica false\n\tLinkedSockets >= 6");
+    /// # assert_eq!(blocks[1].to_string(), "Show # $type->uniques $tier->t1\n\tRarity == Unique\n\tBaseType == \"Vaal Regalia\"\n\tReplica false");
+    /// # assert_eq!(blocks[2].to_string(), "Show # $type->uniques $tier->t2\n\tRarity == Unique\n\tBaseType == \"Vaal Regalia\"\n\tReplica true");
+    /// ```
+    pub fn blocks(&self, prices: &[Price]) -> Vec<Block> {
+        let mut blocks = BTreeMap::<_, Vec<String>>::new();
+        for (group, value) in self.values(prices) {
+            let tier = self.tiers.tier_of(&group.key(), value);
+            blocks
+                .entry((Reverse(group.links), group.replica, group.corrupted, tier))
+                .or_default()
+                .push(group.base_type);
+        }
+        blocks
+            .into_iter()
+            .map(|((Reverse(links), replica, corrupted, tier), base_types)| {
+                let mut block = Block::show()
+                    .comment(format!("$type->uniques $tier->t{}", tier))
+                    .rarity(Operator::Equal, Rarity::Unique)
+                    .base_type_eq(base_types);
+                if let Some(replica) = replica {
+                    block = block.replica(replica);
+                }
+                if let Some(corrupted) = corrupted {
+                    block = block.corrupted(corrupted);
+                }
+                if let Some(links) = links {
+                    block = block.linked_sockets_ge(links);
+                }
+                match self.styles.get(tier) {
+                    Some(style) => style.apply(block),
+                    None => block,
+                }
+            })
+            .collect()
+    }
+}
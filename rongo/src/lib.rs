@@ -0,0 +1,10 @@
+#![deny(missing_docs)]
+//! Generates Path of Exile filters from poe.ninja prices.
+
+pub mod confidence;
+pub mod config;
+pub mod generate;
+pub mod profile;
+pub mod ssf;
+pub mod style;
+pub mod tier;
@@ -1,3 +1,297 @@
+//! Generates and edits Path of Exile filters.
+use libfilter::filter::Filter;
+use libfilter::format::FormatOptions;
+use libfilter::selector::Selector;
+use libninja::client::Client;
+use libninja::history::TrendPolicy;
+use libninja::http::Http;
+use libninja::league;
+use libninja::schema::Mode;
+use libninja::snapshot::SnapshotStore;
+use libninja::source::{Combine, Composite, FileSource};
+use libninja::Category;
+use rongo::confidence::ConfidencePolicy;
+use rongo::config::Config;
+use rongo::generate::recipe::{Collected, Recipe, RecipeKind};
+use rongo::profile::Profile;
+use rongo::ssf::Ssf;
+use rongo::tier::{Assignment, Hysteresis};
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::process;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const USAGE: &str = "usage:
+    rongo query <filter> <selector>    print the blocks picked out by the selector
+    rongo fmt <filter> [output]        rewrite the filter in canonical layout
+    rongo generate <output> [league]   write a filter priced from the snapshots in ./snapshots
+        --offline                      only read snapshots, however old, never fetching
+        --pin <timestamp>              only read snapshots taken at or before a Unix time in
+                                       milliseconds, leaving the kept tiers as they are
+        --prices <sheet>               prefer the prices in a JSON or CSV sheet over poe.ninja
+                                       when taking snapshots; may be given more than once
+    rongo generate --ssf <output> [weights]
+                                       write a solo self-found filter valued by usefulness,
+                                       from a weights file or the curated weights
+    either generate also takes
+        --recipe <chaos|regal> <counts>
+                                       add vendor recipe blocks, with the items already
+                                       collected for each slot read from a counts file
+        --profile <profile>            tailor the filter to the build in a profile file
+    rongo leagues                      list the leagues with economy data
+    rongo update [league]              refresh stale price snapshots in ./snapshots,
+                                       for the current challenge league by default";
+
+/// The user agent sent to the economy source.
+const USER_AGENT: &str = concat!("rongo/", env!("CARGO_PKG_VERSION"));
+
+/// How far back the trends prices are valued by reach.
+const HISTORY: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// The league used when no league is given and the current one cannot be found.
+const FALLBACK_LEAGUE: &str = "Standard";
+
 fn main() {
-    println!("Hello, world!");
+    let args: Vec<String> = env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        Some("query") if args.len() >= 3 => query(&args[1], &args[2..].join(" ")),
+        Some("fmt") if args.len() == 2 => format(&args[1], &args[1]),
+        Some("fmt") if args.len() == 3 => format(&args[1], &args[2]),
+        Some("generate") => match Options::parse(&args[1..]) {
+            Some(options) if options.ssf && options.is_trade() => usage(),
+            Some(options) if options.ssf => match options.positional.as_slice() {
+                [output] => generate_ssf(output, None, &options),
+                [output, weights] => generate_ssf(output, Some(weights), &options),
+                _ => usage(),
+            },
+            Some(options) => match options.positional.as_slice() {
+                [output] => generate(output, None, &options),
+                [output, league] => generate(output, Some(league), &options),
+                _ => usage(),
+            },
+            None => usage(),
+        },
+        Some("leagues") if args.len() == 1 => leagues(),
+        Some("update") if args.len() == 1 => update(None),
+        Some("update") if args.len() == 2 => update(Some(&args[1])),
+        _ => usage(),
+    };
+    if let Err(e) = result {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+}
+
+/// Prints the usage and exits.
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(2);
+}
+
+#[derive(Debug, Default)]
+/// The flags and arguments of `rongo generate`.
+struct Options {
+    /// Whether to write the solo self-found filter.
+    ssf: bool,
+
+    /// Whether to only read snapshots.
+    offline: bool,
+
+    /// The time in milliseconds the snapshots are pinned to.
+    pin: Option<u64>,
+
+    /// The price sheets preferred over poe.ninja, most preferred first.
+    sheets: Vec<String>,
+
+    /// The vendor recipe and the file of items already collected for it.
+    recipe: Option<(RecipeKind, String)>,
+
+    /// The file of the build the filter is tailored to.
+    profile: Option<String>,
+
+    /// The arguments that are not flags, in order.
+    positional: Vec<String>,
+}
+
+impl Options {
+    /// Reads the flags and arguments, or `None` if a flag is unknown or misses its value.
+    fn parse(args: &[String]) -> Option<Options> {
+        let mut options = Options::default();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--ssf" => options.ssf = true,
+                "--offline" => options.offline = true,
+                "--pin" => options.pin = Some(args.next()?.parse().ok()?),
+                "--prices" => options.sheets.push(args.next()?.clone()),
+                "--recipe" => {
+                    let kind = match args.next()?.as_str() {
+                        "chaos" => RecipeKind::Chaos,
+                        "regal" => RecipeKind::Regal,
+                        _ => return None,
+                    };
+                    options.recipe = Some((kind, args.next()?.clone()));
+                }
+                "--profile" => options.profile = Some(args.next()?.clone()),
+                flag if flag.starts_with("--") => return None,
+                _ => options.positional.push(arg.clone()),
+            }
+        }
+        Some(options)
+    }
+
+    /// Whether any flag only applies to filters priced from the trade economy.
+    fn is_trade(&self) -> bool {
+        self.offline || self.pin.is_some() || !self.sheets.is_empty()
+    }
+
+    /// The default generators with the recipe and profile asked for.
+    fn config(&self) -> Result<Config, Box<dyn Error>> {
+        let mut config = Config::default();
+        if let Some((kind, path)) = &self.recipe {
+            config = config.recipe(Recipe::new(*kind), Collected::load(path)?);
+        }
+        if let Some(path) = &self.profile {
+            config = config.profile(Profile::load(path)?);
+        }
+        Ok(config)
+    }
+}
+
+/// Prints every block in the filter at `path` picked out by the selector.
+fn query(path: &str, selector: &str) -> Result<(), Box<dyn Error>> {
+    let filter = Filter::read_from_file(path)?;
+    let selector = selector.parse::<Selector>()?;
+    for block in filter.query(&selector) {
+        println!("{}\n", block);
+    }
+    Ok(())
+}
+
+/// Rewrites the filter at `path` in canonical layout, leaving its imports unresolved.
+fn format(path: &str, output: &str) -> Result<(), Box<dyn Error>> {
+    let filter = fs::read_to_string(path)?.parse::<Filter>()?;
+    filter.write_formatted(output, &FormatOptions::default())?;
+    Ok(())
+}
+
+/// Writes a filter priced from the snapshots of a league, updating stale ones from the price
+/// sheets and poe.ninja unless offline or pinned.
+///
+/// Each price is valued by its trend over the week before now or the pin, left out or capped if
+/// too few are listed, and held in its tier until it has clearly moved, with the tiers of each
+/// category kept next to its snapshots. A pinned filter leaves the kept tiers as they are.
+fn generate(output: &str, league: Option<&str>, options: &Options) -> Result<(), Box<dyn Error>> {
+    let mut store = SnapshotStore::new("snapshots").offline(options.offline);
+    if let Some(pin) = options.pin {
+        store = store.pin(pin);
+    }
+    let mut source = Composite::new(Combine::Prefer);
+    for sheet in &options.sheets {
+        source = source.source(FileSource::new(sheet));
+    }
+    let source = source.source(client(&store));
+    let league = match league {
+        Some(league) => league.to_string(),
+        None => store.default_league(&client(&store), FALLBACK_LEAGUE),
+    };
+    let now = match options.pin {
+        Some(pin) => pin,
+        None => SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as u64,
+    };
+    let since = now.saturating_sub(HISTORY.as_millis() as u64);
+    let mut config = options.config()?;
+    let mut all = BTreeMap::new();
+    for category in Config::categories() {
+        let prices = store.get(&source, &league, category)?.prices;
+        let history = store.history(&league, category, since)?;
+        let prices = TrendPolicy::default().apply(&prices, &history);
+        let (prices, report) = ConfidencePolicy::default().apply(&prices, config.tiers(category));
+        if !report.excluded.is_empty() || !report.capped.is_empty() {
+            print!("{}:\n{}", category, report);
+        }
+        let path = assignment_file(&league, category);
+        let previous = Assignment::load(&path)?;
+        let assignment = config.hold(category, &prices, &previous, &Hysteresis::default());
+        if options.pin.is_none() {
+            assignment.save(&path)?;
+        }
+        all.insert(category, prices);
+    }
+    let filter =
+        config.filter(|category| Ok::<_, Infallible>(all.remove(&category).unwrap_or_default()))?;
+    filter.write_to_file(output)?;
+    Ok(())
+}
+
+/// The file the tiers of a category are kept in, next to its snapshots.
+fn assignment_file(league: &str, category: Category) -> String {
+    format!(
+        "snapshots/{}/{}/tiers.json",
+        league.replace(['/', '\\'], "_"),
+        category
+    )
+}
+
+/// Writes a solo self-found filter from the usefulness weights in a file, or the curated ones.
+fn generate_ssf(
+    output: &str,
+    weights: Option<&str>,
+    options: &Options,
+) -> Result<(), Box<dyn Error>> {
+    let ssf = match weights {
+        Some(path) => Ssf::load(path)?,
+        None => Ssf::curated(),
+    };
+    let filter = options.config()?.ssf_filter(&ssf);
+    filter.write_to_file(output)?;
+    Ok(())
+}
+
+/// A poe.ninja client that keeps its HTTP cache next to the snapshots of the store, and reads
+/// responses that changed shape as far as it can, keeping the drift on the snapshot.
+fn client(store: &SnapshotStore) -> Client {
+    Client::new().mode(Mode::Lenient).http(
+        Http::new()
+            .user_agent(USER_AGENT)
+            .cache_file(store.http_cache()),
+    )
+}
+
+/// Prints the leagues with economy data, marking the default one.
+fn leagues() -> Result<(), Box<dyn Error>> {
+    let store = SnapshotStore::new("snapshots");
+    let leagues = store.leagues(&client(&store))?;
+    let default = league::default_league(&leagues);
+    for league in &leagues {
+        let marker = if Some(league) == default { "*" } else { " " };
+        println!("{} {}", marker, league.name);
+    }
+    Ok(())
+}
+
+/// Fetches every category whose newest snapshot is stale.
+fn update(league: Option<&str>) -> Result<(), Box<dyn Error>> {
+    let store = SnapshotStore::new("snapshots");
+    let client = client(&store);
+    let league = match league {
+        Some(league) => league.to_string(),
+        None => store.default_league(&client, FALLBACK_LEAGUE),
+    };
+    let league = league.as_str();
+    println!("league: {}", league);
+    for category in Category::ALL {
+        let snapshot = store.get(&client, league, category)?;
+        println!("{}: {} prices", category, snapshot.prices.len());
+        if let Some(drift) = &snapshot.drift {
+            println!("  drift in {}", drift);
+            for (name, what) in &drift.affected {
+                println!("    {}: {}", name, what);
+            }
+        }
+    }
+    Ok(())
 }
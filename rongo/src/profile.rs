@@ -0,0 +1,324 @@
+//! Tailoring a filter to a build.
+//!
+//! A [Profile] says which weapons, armour attributes and socket colors a build uses and which
+//! uniques it is after. Applied to a filter, the part of each block showing other weapons and
+//! armour bases is turned into a `Hide` block in its place, so those items are hidden rather
+//! than falling through to later blocks. Socket colors of the build's own blocks are rewritten
+//! to the build's colors, and the build's uniques are shown first.
+use crate::style::Style;
+use libfilter::block::Block;
+use libfilter::filter::Filter;
+use libfilter::line::condition::Condition;
+use libfilter::line::Line;
+use libfilter::section::Section;
+use libfilter::{Operator, Rarity};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::fs;
+use std::io::Error;
+use std::mem;
+
+/// The weapon classes a profile chooses between.
+pub const WEAPON_CLASSES: [&str; 15] = [
+    "Bows",
+    "Claws",
+    "Daggers",
+    "One Hand Axes",
+    "One Hand Maces",
+    "One Hand Swords",
+    "Rune Daggers",
+    "Sceptres",
+    "Staves",
+    "Thrusting One Hand Swords",
+    "Two Hand Axes",
+    "Two Hand Maces",
+    "Two Hand Swords",
+    "Wands",
+    "Warstaves",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+/// The attributes an armour base requires.
+pub enum Attribute {
+    /// Strength, for armour.
+    Str,
+    /// Dexterity, for evasion.
+    Dex,
+    /// Intelligence, for energy shield.
+    Int,
+    /// Strength and dexterity.
+    StrDex,
+    /// Strength and intelligence.
+    StrInt,
+    /// Dexterity and intelligence.
+    DexInt,
+}
+
+/// The endgame armour bases, with the attributes they require.
+pub const ARMOUR_BASES: [(&str, Attribute); 40] = [
+    ("Glorious Plate", Attribute::Str),
+    ("Astral Plate", Attribute::Str),
+    ("Zodiac Leather", Attribute::Dex),
+    ("Assassin's Garb", Attribute::Dex),
+    ("Vaal Regalia", Attribute::Int),
+    ("Occultist's Vestment", Attribute::Int),
+    ("Triumphant Lamellar", Attribute::StrDex),
+    ("General's Brigandine", Attribute::StrDex),
+    ("Saintly Chainmail", Attribute::StrInt),
+    ("Saint's Hauberk", Attribute::StrInt),
+    ("Carnal Armour", Attribute::DexInt),
+    ("Sadist Garb", Attribute::DexInt),
+    ("Royal Burgonet", Attribute::Str),
+    ("Eternal Burgonet", Attribute::Str),
+    ("Lion Pelt", Attribute::Dex),
+    ("Hubris Circlet", Attribute::Int),
+    ("Nightmare Bascinet", Attribute::StrDex),
+    ("Bone Helmet", Attribute::StrInt),
+    ("Prophet Crown", Attribute::StrInt),
+    ("Deicide Mask", Attribute::DexInt),
+    ("Titan Gauntlets", Attribute::Str),
+    ("Spiked Gloves", Attribute::Str),
+    ("Slink Gloves", Attribute::Dex),
+    ("Gripped Gloves", Attribute::Dex),
+    ("Sorcerer Gloves", Attribute::Int),
+    ("Fingerless Silk Gloves", Attribute::Int),
+    ("Dragonscale Gauntlets", Attribute::StrDex),
+    ("Crusader Gloves", Attribute::StrInt),
+    ("Murder Mitts", Attribute::DexInt),
+    ("Titan Greaves", Attribute::Str),
+    ("Slink Boots", Attribute::Dex),
+    ("Sorcerer Boots", Attribute::Int),
+    ("Dragonscale Boots", Attribute::StrDex),
+    ("Crusader Boots", Attribute::StrInt),
+    ("Murder Boots", Attribute::DexInt),
+    ("Pinnacle Tower Shield", Attribute::Str),
+    ("Imperial Buckler", Attribute::Dex),
+    ("Titanium Spirit Shield", Attribute::Int),
+    ("Archon Kite Shield", Attribute::StrInt),
+    ("Supreme Spiked Shield", Attribute::DexInt),
+];
+
+/// The tags of blocks whose socket colors are the build's.
+const BUILD_SOCKET_TAGS: [&str; 2] = ["$type->profile", "$rule->sockets"];
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+/// What a build uses.
+///
+/// Empty weapon classes or attributes leave those blocks alone, so a profile only narrows what
+/// it declares.
+///
+/// # Example
+/// ```
+/// # use rongo::profile::Profile;
+/// # use libfilter::filter::Filter;
+/// let profile: Profile = serde_json::from_str(r#"{
+///     "weapon_classes": ["Wands"],
+///     "attributes": ["Int", "DexInt"],
+///     "socket_colors": "BBBG",
+///     "uniques": ["Hubris Circlet"]
+/// }"#).unwrap();
+/// let mut filter = "###0001\n###Rares\n\
+///     Show\n\tClass \"Wands\" \"Two Hand Swords\"\n\tRarity == Rare\n\
+///     Show\n\tClass \"Two Hand Axes\"\n\
+///     Show\n\tBaseType \"Glorious Plate\" \"Vaal Regalia\"\n\
+///     Show # $type->leveling $rule->sockets\n\tSocketGroup >= 3RRR\n\
+///     Show # $type->recipe\n\tSocketGroup RGB\n".parse::<Filter>().unwrap();
+///
+/// let report = profile.apply(&mut filter);
+/// # let blocks = filter.blocks().map(|block| block.to_string()).collect::<Vec<_>>();
+/// # assert_eq!(blocks[0].lines().take(3).collect::<Vec<_>>(), ["Show # $type->profile $uniques", "\tRarity == Unique", "\tBaseType \"Hubris Circlet\""]);
+/// # assert_eq!(blocks[1], "Show\n\tClass == \"Wands\"\n\tRarity == Rare");
+/// # assert_eq!(blocks[2], "Hide # $offbuild\n\tClass == \"Two Hand Swords\"\n\tRarity == Rare");
+/// # assert_eq!(blocks[3], "Hide # $offbuild\n\tClass == \"Two Hand Axes\"");
+/// # assert_eq!(blocks[4], "Show\n\tBaseType == \"Vaal Regalia\"");
+/// # assert_eq!(blocks[5], "Hide # $offbuild\n\tBaseType == \"Glorious Plate\"");
+/// # assert_eq!(blocks[6], "Show # $type->leveling $rule->sockets\n\tSocketGroup >= 3BBB");
+/// # assert_eq!(blocks[7], "Show # $type->recipe\n\tSocketGroup RGB");
+/// # assert_eq!(report.to_string(), "hid 1 blocks, narrowed 2, recolored 1");
+/// ```
+pub struct Profile {
+    /// The weapon classes the build uses, such as `Wands`.
+    pub weapon_classes: BTreeSet<String>,
+
+    /// The armour attributes the build wears.
+    pub attributes: BTreeSet<Attribute>,
+
+    /// The socket colors the build links, such as `BBBG`.
+    pub socket_colors: Option<String>,
+
+    /// The base types of the uniques the build is after.
+    pub uniques: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// How many blocks a [Profile] changed.
+pub struct ProfileReport {
+    /// Blocks hidden because everything they showed is off-build.
+    pub hidden: usize,
+
+    /// Blocks whose classes or base types were narrowed, with the rest hidden.
+    pub narrowed: usize,
+
+    /// Blocks whose socket colors were rewritten.
+    pub recolored: usize,
+}
+
+impl Attribute {
+    /// The attributes of an armour base, if it is one of [ARMOUR_BASES].
+    pub fn of(base_type: &str) -> Option<Attribute> {
+        ARMOUR_BASES
+            .iter()
+            .find(|(name, _)| *name == base_type)
+            .map(|(_, attribute)| *attribute)
+    }
+}
+
+impl Profile {
+    /// Reads a profile from a file.
+    pub fn load(path: &str) -> Result<Profile, Error> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /// Whether an item class or base type is used by the build.
+    fn wants(&self, value: &str) -> bool {
+        if !self.weapon_classes.is_empty() && WEAPON_CLASSES.contains(&value) {
+            return self.weapon_classes.contains(value);
+        }
+        match Attribute::of(value) {
+            Some(attribute) if !self.attributes.is_empty() => self.attributes.contains(&attribute),
+            _ => true,
+        }
+    }
+
+    /// Tailors the shown blocks of a filter to the build.
+    ///
+    /// The off-build part of a block becomes a `Hide` block tagged `$offbuild` in its place,
+    /// matching off-build classes and base types exactly, and the rest stays shown. Socket
+    /// colors are only rewritten in the profile's own blocks and blocks tagged
+    /// `$rule->sockets`, which are the build's sockets. Hidden blocks and blocks for uniques
+    /// are left alone.
+    pub fn apply(&self, filter: &mut Filter) -> ProfileReport {
+        let mut report = ProfileReport::default();
+        for section in &mut filter.sections {
+            for mut block in mem::take(&mut section.blocks) {
+                if block.is_hidden || is_unique(&block) {
+                    section.blocks.push(block);
+                    continue;
+                }
+                if block.tags().any(|tag| BUILD_SOCKET_TAGS.contains(&tag)) {
+                    report.recolored += usize::from(self.recolor(&mut block));
+                }
+                let (shown, hidden) = self.split(&block);
+                match (shown, hidden.is_empty()) {
+                    (Some(shown), true) => section.blocks.push(shown),
+                    (Some(shown), false) => {
+                        report.narrowed += 1;
+                        section.blocks.push(shown);
+                        section.blocks.extend(hidden);
+                    }
+                    (None, _) => {
+                        report.hidden += 1;
+                        section.blocks.extend(hidden);
+                    }
+                }
+            }
+        }
+        if !self.uniques.is_empty() {
+            let block = Block::show()
+                .comment("$type->profile $uniques")
+                .rarity(Operator::Equal, Rarity::Unique)
+                .base_type(self.uniques.iter().cloned());
+            let block = Style::ladder(2)[0].apply(block);
+            match filter.sections.first_mut() {
+                Some(section) => section.blocks.insert(0, block),
+                None => filter.add_section(Section::new("").block(block)),
+            }
+        }
+        report
+    }
+
+    /// Rewrites the socket colors of a block to the build's, returning whether any changed.
+    fn recolor(&self, block: &mut Block) -> bool {
+        let Some(wanted) = &self.socket_colors else {
+            return false;
+        };
+        let mut recolored = false;
+        for line in &mut block.lines {
+            if let Line::Condition(Condition::SocketGroup((_, colors, count))) = line {
+                let length = match *count {
+                    0 => colors.len(),
+                    count => usize::from(count),
+                };
+                let wanted = wanted.chars().take(length).collect::<String>();
+                recolored |= *colors != wanted;
+                *colors = wanted;
+            }
+        }
+        recolored
+    }
+
+    /// The part of a block showing what the build wants, if any, and a `Hide` block for each
+    /// class or base type line with off-build values.
+    fn split(&self, block: &Block) -> (Option<Block>, Vec<Block>) {
+        let mut shown = Some(block.clone());
+        let mut hidden = vec![];
+        for (index, line) in block.lines.iter().enumerate() {
+            let values = match line {
+                Line::Condition(Condition::Class((_, values)))
+                | Line::Condition(Condition::BaseType((_, values))) => values,
+                _ => continue,
+            };
+            let (wanted, unwanted): (Vec<_>, Vec<_>) =
+                values.iter().cloned().partition(|value| self.wants(value));
+            if unwanted.is_empty() {
+                continue;
+            }
+            let mut hide = block.clone();
+            hide.is_hidden = true;
+            hide.comment = Some(match &block.comment {
+                Some(comment) => format!("{} $offbuild", comment),
+                None => String::from("$offbuild"),
+            });
+            hide.lines[index] = exactly(line, unwanted);
+            hidden.push(hide);
+            shown = shown.filter(|_| !wanted.is_empty()).map(|mut shown| {
+                shown.lines[index] = exactly(line, wanted);
+                shown
+            });
+        }
+        (shown, hidden)
+    }
+}
+
+/// A class or base type line matching exactly the values, so a name is not matched as part of
+/// a longer one.
+fn exactly(line: &Line, values: Vec<String>) -> Line {
+    match line {
+        Line::Condition(Condition::Class(_)) => {
+            Line::Condition(Condition::Class((Some(Operator::Equal), values)))
+        }
+        _ => Line::Condition(Condition::BaseType((Some(Operator::Equal), values))),
+    }
+}
+
+/// Whether a block only matches uniques.
+fn is_unique(block: &Block) -> bool {
+    block.lines.iter().any(|line| match line {
+        Line::Condition(Condition::Rarity((Operator::Equal, rarities))) => {
+            rarities == &[Rarity::Unique]
+        }
+        _ => false,
+    })
+}
+
+impl fmt::Display for ProfileReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "hid {} blocks, narrowed {}, recolored {}",
+            self.hidden, self.narrowed, self.recolored
+        )
+    }
+}
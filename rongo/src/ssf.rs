@@ -0,0 +1,215 @@
+//! Values for solo self-found play, where trade prices mean nothing.
+//!
+//! An SSF player can only use what drops, so items are valued by how useful they are for
+//! crafting and progression, scaled up by how rarely they drop. The values stand in for
+//! poe.ninja prices, so the same generators and tier thresholds build the filter.
+use libninja::{Category, Price};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Error;
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize,
+)]
+/// How rarely an item drops.
+pub enum Scarcity {
+    /// Drops all the time.
+    #[default]
+    Common,
+    /// Drops a few times a map.
+    Uncommon,
+    /// Drops a few times a day.
+    Rare,
+    /// May never drop for a player.
+    Exceptional,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+/// How useful an item is, and how rarely it drops.
+pub struct Useful {
+    /// How useful the item is, on the scale of the tier thresholds.
+    pub usefulness: f64,
+
+    /// How rarely the item drops.
+    #[serde(default)]
+    pub scarcity: Scarcity,
+}
+
+impl Scarcity {
+    /// How much rarer drops are worth than common ones.
+    pub fn multiplier(self) -> f64 {
+        match self {
+            Scarcity::Common => 1.0,
+            Scarcity::Uncommon => 3.0,
+            Scarcity::Rare => 10.0,
+            Scarcity::Exceptional => 30.0,
+        }
+    }
+}
+
+impl Useful {
+    /// The value of the item, its usefulness scaled by its scarcity.
+    pub fn value(&self) -> f64 {
+        self.usefulness * self.scarcity.multiplier()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Usefulness weights for solo self-found play.
+///
+/// # Example
+/// ```
+/// # use rongo::ssf::Ssf;
+/// # use libninja::Category;
+/// let ssf = Ssf::curated();
+/// let currency = ssf.prices(Category::Currency);
+/// let divine = currency.iter().find(|price| price.name == "Divine Orb").unwrap();
+/// # assert_eq!(divine.chaos_value, 400.0);
+/// # assert!(ssf.prices(Category::BaseType).iter().all(|price| price.item_level == Some(84)));
+/// # assert!(ssf.prices(Category::DivinationCard).is_empty());
+/// ```
+pub struct Ssf {
+    /// Currency, by name.
+    pub currency: BTreeMap<String, Useful>,
+
+    /// Crafting bases, by base type.
+    pub bases: BTreeMap<String, Useful>,
+
+    /// Uniques, by base type, valued as the best unique of that base.
+    pub uniques: BTreeMap<String, Useful>,
+
+    /// The item level crafting bases are highlighted from.
+    pub item_level: u8,
+}
+
+/// The curated currency weights, with usefulness and scarcity.
+const CURRENCY: [(&str, f64, Scarcity); 22] = [
+    ("Mirror of Kalandra", 100.0, Scarcity::Exceptional),
+    ("Divine Orb", 40.0, Scarcity::Rare),
+    ("Exalted Orb", 30.0, Scarcity::Rare),
+    ("Orb of Annulment", 25.0, Scarcity::Rare),
+    ("Gemcutter's Prism", 20.0, Scarcity::Rare),
+    ("Chaos Orb", 10.0, Scarcity::Uncommon),
+    ("Vaal Orb", 10.0, Scarcity::Uncommon),
+    ("Orb of Fusing", 8.0, Scarcity::Uncommon),
+    ("Orb of Scouring", 6.0, Scarcity::Uncommon),
+    ("Regal Orb", 6.0, Scarcity::Uncommon),
+    ("Blessed Orb", 4.0, Scarcity::Uncommon),
+    ("Glassblower's Bauble", 3.0, Scarcity::Uncommon),
+    ("Orb of Alchemy", 5.0, Scarcity::Common),
+    ("Jeweller's Orb", 4.0, Scarcity::Common),
+    ("Cartographer's Chisel", 3.0, Scarcity::Common),
+    ("Orb of Alteration", 2.0, Scarcity::Common),
+    ("Chromatic Orb", 2.0, Scarcity::Common),
+    ("Orb of Transmutation", 1.0, Scarcity::Common),
+    ("Orb of Augmentation", 1.0, Scarcity::Common),
+    ("Armourer's Scrap", 0.2, Scarcity::Common),
+    ("Blacksmith's Whetstone", 0.2, Scarcity::Common),
+    ("Scroll of Wisdom", 0.1, Scarcity::Common),
+];
+
+/// The curated crafting base weights, with usefulness and scarcity.
+const BASES: [(&str, f64, Scarcity); 12] = [
+    ("Stygian Vise", 20.0, Scarcity::Rare),
+    ("Opal Ring", 10.0, Scarcity::Rare),
+    ("Vermillion Ring", 10.0, Scarcity::Rare),
+    ("Crystal Belt", 8.0, Scarcity::Rare),
+    ("Marble Amulet", 8.0, Scarcity::Rare),
+    ("Vaal Regalia", 15.0, Scarcity::Uncommon),
+    ("Hubris Circlet", 10.0, Scarcity::Uncommon),
+    ("Onyx Amulet", 10.0, Scarcity::Uncommon),
+    ("Sorcerer Boots", 8.0, Scarcity::Uncommon),
+    ("Sorcerer Gloves", 8.0, Scarcity::Uncommon),
+    ("Two-Stone Ring", 6.0, Scarcity::Uncommon),
+    ("Titanium Spirit Shield", 6.0, Scarcity::Uncommon),
+];
+
+/// The curated unique weights by base type, with usefulness and scarcity.
+const UNIQUES: [(&str, f64, Scarcity); 7] = [
+    ("Leather Belt", 100.0, Scarcity::Exceptional),
+    ("Prismatic Jewel", 40.0, Scarcity::Exceptional),
+    ("Carnal Armour", 30.0, Scarcity::Rare),
+    ("Sadist Garb", 20.0, Scarcity::Rare),
+    ("Gold Ring", 10.0, Scarcity::Rare),
+    ("Sapphire Flask", 10.0, Scarcity::Uncommon),
+    ("Simple Robe", 10.0, Scarcity::Uncommon),
+];
+
+impl Ssf {
+    /// The curated weights, highlighting bases from item level 84.
+    pub fn curated() -> Ssf {
+        let table = |entries: &[(&str, f64, Scarcity)]| {
+            entries
+                .iter()
+                .map(|(name, usefulness, scarcity)| {
+                    let useful = Useful {
+                        usefulness: *usefulness,
+                        scarcity: *scarcity,
+                    };
+                    (name.to_string(), useful)
+                })
+                .collect()
+        };
+        Ssf {
+            currency: table(&CURRENCY),
+            bases: table(&BASES),
+            uniques: table(&UNIQUES),
+            item_level: 84,
+        }
+    }
+
+    /// Reads weights from a file.
+    pub fn load(path: &str) -> Result<Ssf, Error> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /// Writes the weights to a file.
+    pub fn save(&self, path: &str) -> Result<(), Error> {
+        fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+
+    /// Stand-in prices for a category, valued by usefulness. Uniques are all given as
+    /// [Category::UniqueArmour], since they are tiered together, and categories without
+    /// weights have none.
+    pub fn prices(&self, category: Category) -> Vec<Price> {
+        match category {
+            Category::Currency => self
+                .currency
+                .iter()
+                .map(|(name, useful)| Price {
+                    name: name.clone(),
+                    chaos_value: useful.value(),
+                    ..Price::default()
+                })
+                .collect(),
+            Category::BaseType => self
+                .bases
+                .iter()
+                .map(|(name, useful)| Price {
+                    name: name.clone(),
+                    chaos_value: useful.value(),
+                    item_level: Some(self.item_level),
+                    ..Price::default()
+                })
+                .collect(),
+            Category::UniqueArmour => self
+                .uniques
+                .iter()
+                .map(|(base_type, useful)| Price {
+                    name: base_type.clone(),
+                    base_type: Some(base_type.clone()),
+                    chaos_value: useful.value(),
+                    ..Price::default()
+                })
+                .collect(),
+            _ => vec![],
+        }
+    }
+}
+
+impl Default for Ssf {
+    fn default() -> Ssf {
+        Ssf::curated()
+    }
+}
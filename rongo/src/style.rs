@@ -0,0 +1,181 @@
+//! How the blocks of a tier look.
+use libfilter::block::Block;
+use libfilter::{Color, Shape, Size, RGBA};
+
+#[derive(Debug, Clone, PartialEq)]
+/// How the blocks of a tier look. Unset actions are left to the game's defaults.
+pub struct Style {
+    /// Whether items are hidden.
+    pub hidden: bool,
+
+    /// The font size.
+    pub font_size: Option<u16>,
+
+    /// The text color.
+    pub text_color: Option<RGBA>,
+
+    /// The border color.
+    pub border_color: Option<RGBA>,
+
+    /// The background color.
+    pub background_color: Option<RGBA>,
+
+    /// The alert sound id and volume.
+    pub alert_sound: Option<(u8, u16)>,
+
+    /// The minimap icon.
+    pub minimap_icon: Option<(Size, Color, Shape)>,
+
+    /// The color of a permanent light beam.
+    pub beam: Option<Color>,
+}
+
+impl Style {
+    /// A style that shows items without changing how they look.
+    pub fn plain() -> Style {
+        Style {
+            hidden: false,
+            font_size: None,
+            text_color: None,
+            border_color: None,
+            background_color: None,
+            alert_sound: None,
+            minimap_icon: None,
+            beam: None,
+        }
+    }
+
+    /// A style that hides items.
+    pub fn hidden() -> Style {
+        Style {
+            hidden: true,
+            ..Style::plain()
+        }
+    }
+
+    /// One style for each of `count` tiers, from most to least eye-catching.
+    ///
+    /// The top tiers get beams, icons and sounds, the middle ones colors, and the last is plain.
+    ///
+    /// # Example
+    /// ```
+    /// # use rongo::style::Style;
+    /// # use libfilter::block::Block;
+    /// let styles = Style::ladder(4);
+    /// # assert_eq!(styles.len(), 4);
+    /// # assert_eq!(styles[3], Style::plain());
+    /// let block = styles[0].apply(Block::show().class(["Currency"]));
+    /// # assert_eq!(block.to_string(), "Show\n\tClass \"Currency\"\n\tSetFontSize 45\n\tSetTextColor 255 0 0 255\n\tSetBorderColor 255 0 0 255\n\tSetBackgroundColor 255 255 255 255\n\tPlayAlertSound 6 300\n\tMinimapIcon Largest Red Star\n\tPlayEffect Red");
+    /// ```
+    pub fn ladder(count: usize) -> Vec<Style> {
+        let red = RGBA {
+            r: 255,
+            g: 0,
+            b: 0,
+            a: 255,
+        };
+        let white = RGBA {
+            r: 255,
+            g: 255,
+            b: 255,
+            a: 255,
+        };
+        let orange = RGBA {
+            r: 255,
+            g: 140,
+            b: 0,
+            a: 255,
+        };
+        let yellow = RGBA {
+            r: 255,
+            g: 220,
+            b: 0,
+            a: 255,
+        };
+        let top = Style {
+            hidden: false,
+            font_size: Some(45),
+            text_color: Some(red),
+            border_color: Some(red),
+            background_color: Some(white),
+            alert_sound: Some((6, 300)),
+            minimap_icon: Some((Size::Largest, Color::Red, Shape::Star)),
+            beam: Some(Color::Red),
+        };
+        let high = Style {
+            font_size: Some(42),
+            text_color: Some(orange),
+            border_color: Some(orange),
+            background_color: Some(RGBA {
+                r: 40,
+                g: 20,
+                b: 0,
+                a: 255,
+            }),
+            alert_sound: Some((1, 250)),
+            minimap_icon: Some((Size::Medium, Color::Orange, Shape::Diamond)),
+            beam: Some(Color::Orange),
+            ..top.clone()
+        };
+        let medium = Style {
+            font_size: Some(38),
+            text_color: Some(yellow),
+            border_color: Some(yellow),
+            background_color: None,
+            alert_sound: None,
+            minimap_icon: Some((Size::Small, Color::Yellow, Shape::Circle)),
+            beam: None,
+            ..high.clone()
+        };
+        let low = Style {
+            font_size: Some(34),
+            border_color: Some(RGBA {
+                r: 150,
+                g: 150,
+                b: 150,
+                a: 255,
+            }),
+            text_color: None,
+            minimap_icon: None,
+            ..medium.clone()
+        };
+        let mut ladder = vec![top, high, medium, low];
+        if count <= ladder.len() {
+            ladder.truncate(count.saturating_sub(1));
+        } else {
+            let filler = ladder[ladder.len() - 1].clone();
+            ladder.resize(count - 1, filler);
+        }
+        if count > 0 {
+            ladder.push(Style::plain());
+        }
+        ladder
+    }
+
+    /// Sets the visibility and actions of a block.
+    pub fn apply(&self, mut block: Block) -> Block {
+        block.is_hidden = self.hidden;
+        if let Some(size) = self.font_size {
+            block = block.font_size(size);
+        }
+        if let Some(color) = self.text_color {
+            block = block.text_color(color);
+        }
+        if let Some(color) = self.border_color {
+            block = block.border_color(color);
+        }
+        if let Some(color) = self.background_color {
+            block = block.background_color(color);
+        }
+        if let Some((id, volume)) = self.alert_sound {
+            block = block.alert_sound(id, volume);
+        }
+        if let Some((size, color, shape)) = self.minimap_icon {
+            block = block.minimap_icon(size, color, shape);
+        }
+        if let Some(color) = self.beam {
+            block = block.beam(color);
+        }
+        block
+    }
+}
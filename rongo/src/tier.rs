@@ -0,0 +1,260 @@
+//! Sorting items into tiers by value.
+use libninja::Price;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Error;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+/// Value thresholds in Chaos Orbs, highest first. Tier 0 is the most valuable.
+///
+/// An item is in the first tier whose threshold its value reaches, or in the last tier,
+/// `thresholds.len()`, if it reaches none, unless it is held in a tier.
+///
+/// # Example
+/// ```
+/// # use rongo::tier::TierList;
+/// let tiers = TierList::new(vec![100.0, 10.0, 1.0]).hold("Chaos Orb", 2);
+/// # assert_eq!(tiers.tier(150.0), 0);
+/// # assert_eq!(tiers.tier(10.0), 1);
+/// # assert_eq!(tiers.tier(0.5), 3);
+/// # assert_eq!(tiers.tier_of("Chaos Orb", 10.0), 2);
+/// # assert_eq!(tiers.tier_of("Vaal Orb", 10.0), 1);
+/// ```
+pub struct TierList {
+    /// The lowest value of each tier, highest first.
+    pub thresholds: Vec<f64>,
+
+    /// The tier items are held in whatever their value, keyed as the generator tiers them.
+    pub held: BTreeMap<String, usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+/// The tier of each item, kept between updates.
+pub struct Assignment {
+    /// The tier of each item.
+    pub tiers: BTreeMap<String, usize>,
+
+    /// Items whose value has been in another tier, with that tier and the number of updates in
+    /// a row it has been there.
+    #[serde(default)]
+    pub pending: BTreeMap<String, (usize, u32)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// How reluctant items are to change tier.
+///
+/// An item moves as soon as its value passes a threshold by `margin`, as far as the thresholds
+/// it passes by that much, or into the tier of its value once its value has been in another
+/// tier for `consecutive` updates in a row.
+pub struct Hysteresis {
+    /// How far past a threshold a value must be to move at once, as a fraction of the threshold.
+    pub margin: f64,
+
+    /// How many updates in a row a value must be in another tier to move without passing the
+    /// margin. `None` only moves items that pass the margin.
+    pub consecutive: Option<u32>,
+}
+
+impl Default for Hysteresis {
+    fn default() -> Hysteresis {
+        Hysteresis {
+            margin: 0.1,
+            consecutive: Some(3),
+        }
+    }
+}
+
+impl TierList {
+    /// Tiers with thresholds, holding no item.
+    pub fn new(thresholds: Vec<f64>) -> TierList {
+        TierList {
+            thresholds,
+            held: BTreeMap::new(),
+        }
+    }
+
+    /// Holds an item in a tier.
+    pub fn hold(mut self, key: impl Into<String>, tier: usize) -> TierList {
+        self.held.insert(key.into(), tier);
+        self
+    }
+
+    /// The tier of a value.
+    pub fn tier(&self, value: f64) -> usize {
+        self.thresholds
+            .iter()
+            .position(|threshold| value >= *threshold)
+            .unwrap_or(self.thresholds.len())
+    }
+
+    /// The tier of an item: the tier it is held in, or the tier of its value.
+    pub fn tier_of(&self, key: &str, value: f64) -> usize {
+        match self.held.get(key) {
+            Some(tier) => *tier,
+            None => self.tier(value),
+        }
+    }
+
+    /// The smallest stack that reaches each tier a stack of up to `max` items can reach, given
+    /// the value of a stack of each size. Each stack is held by its [stack_key].
+    ///
+    /// # Example
+    /// ```
+    /// # use rongo::tier::TierList;
+    /// let tiers = TierList::new(vec![10.0, 2.0]);
+    /// let breakpoints = tiers.breakpoints("Chaos Shard", 20, |stack| 0.5 * f64::from(stack));
+    /// # assert_eq!(breakpoints.into_iter().collect::<Vec<_>>(), vec![(0, 20), (1, 4), (2, 1)]);
+    /// let tiers = tiers.hold("Chaos Shard x4", 2);
+    /// let breakpoints = tiers.breakpoints("Chaos Shard", 20, |stack| 0.5 * f64::from(stack));
+    /// # assert_eq!(breakpoints.into_iter().collect::<Vec<_>>(), vec![(0, 20), (1, 5), (2, 1)]);
+    /// ```
+    pub fn breakpoints(
+        &self,
+        key: &str,
+        max: u8,
+        value: impl Fn(u8) -> f64,
+    ) -> BTreeMap<usize, u8> {
+        let mut breakpoints = BTreeMap::new();
+        for stack in 1..=max.max(1) {
+            let tier = self.tier_of(&stack_key(key, stack), value(stack));
+            breakpoints.entry(tier).or_insert(stack);
+        }
+        breakpoints
+    }
+
+    /// The tier of each item by its value alone.
+    pub fn assign<'a>(&self, values: impl IntoIterator<Item = (&'a str, f64)>) -> Assignment {
+        Assignment {
+            tiers: values
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), self.tier(value)))
+                .collect(),
+            pending: BTreeMap::new(),
+        }
+    }
+
+    /// The tier of each item, keeping items in their previous tier until the hysteresis lets
+    /// them move. Items without a previous tier are tiered by value alone.
+    ///
+    /// # Example
+    /// ```
+    /// # use rongo::tier::{Assignment, Hysteresis, TierList};
+    /// let tiers = TierList::new(vec![100.0, 10.0]);
+    /// let hysteresis = Hysteresis { margin: 0.1, consecutive: Some(2) };
+    /// let first = tiers.assign([("Chaos Orb", 9.0)]);
+    ///
+    /// // Just past the threshold: held back once, then moved.
+    /// let second = tiers.assign_with_hysteresis([("Chaos Orb", 10.5)], &first, &hysteresis);
+    /// # assert_eq!(second.tiers["Chaos Orb"], 2);
+    /// # assert_eq!(second.pending["Chaos Orb"], (1, 1));
+    /// let third = tiers.assign_with_hysteresis([("Chaos Orb", 10.5)], &second, &hysteresis);
+    /// # assert_eq!(third.tiers["Chaos Orb"], 1);
+    ///
+    /// // Moves towards different tiers do not add up.
+    /// let held = tiers.assign([("Vaal Orb", 50.0)]);
+    /// let up = tiers.assign_with_hysteresis([("Vaal Orb", 105.0)], &held, &hysteresis);
+    /// let down = tiers.assign_with_hysteresis([("Vaal Orb", 9.5)], &up, &hysteresis);
+    /// # assert_eq!(down.tiers["Vaal Orb"], 1);
+    /// # assert_eq!(down.pending["Vaal Orb"], (2, 1));
+    ///
+    /// // Well past the threshold: moved at once.
+    /// let jump = tiers.assign_with_hysteresis([("Chaos Orb", 12.0)], &first, &hysteresis);
+    /// # assert_eq!(jump.tiers["Chaos Orb"], 1);
+    /// # assert_eq!(jump.pending, Assignment::default().pending);
+    ///
+    /// // Two tiers up, but only the first threshold passed by the margin: moved one tier, then
+    /// // the rest of the way once the value has stayed.
+    /// let two = tiers.assign_with_hysteresis([("Chaos Orb", 105.0)], &first, &hysteresis);
+    /// # assert_eq!(two.tiers["Chaos Orb"], 1);
+    /// # assert_eq!(two.pending["Chaos Orb"], (0, 1));
+    /// let stayed = tiers.assign_with_hysteresis([("Chaos Orb", 105.0)], &two, &hysteresis);
+    /// # assert_eq!(stayed.tiers["Chaos Orb"], 0);
+    ///
+    /// // Both thresholds passed by the margin: moved two tiers at once.
+    /// let both = tiers.assign_with_hysteresis([("Chaos Orb", 120.0)], &first, &hysteresis);
+    /// # assert_eq!(both.tiers["Chaos Orb"], 0);
+    /// ```
+    pub fn assign_with_hysteresis<'a>(
+        &self,
+        values: impl IntoIterator<Item = (&'a str, f64)>,
+        previous: &Assignment,
+        hysteresis: &Hysteresis,
+    ) -> Assignment {
+        let mut assignment = Assignment::default();
+        for (name, value) in values {
+            let tier = self.tier(value);
+            let held = match previous.tiers.get(name) {
+                Some(held) => *held,
+                None => {
+                    assignment.tiers.insert(name.to_string(), tier);
+                    continue;
+                }
+            };
+            let up = self.tier(value / (1.0 + hysteresis.margin));
+            let down = self.tier(value / (1.0 - hysteresis.margin).max(f64::EPSILON));
+            let count = match previous.pending.get(name) {
+                Some((target, count)) if *target == tier => count + 1,
+                _ => 1,
+            };
+            // Only the thresholds passed by the margin are crossed at once.
+            let cleared = if up < held {
+                up
+            } else if down > held {
+                down
+            } else {
+                held
+            };
+            let persisted = tier != held && hysteresis.consecutive.is_some_and(|n| count >= n);
+            let moved = if persisted { tier } else { cleared };
+            if moved != tier {
+                assignment.pending.insert(name.to_string(), (tier, count));
+            }
+            assignment.tiers.insert(name.to_string(), moved);
+        }
+        assignment
+    }
+}
+
+/// The name of an item with whatever else tells it apart, such as `Vaal Grace 21/20 corrupted`.
+///
+/// Items are held by their label, or by the [stack_key] of their label for generators that
+/// tier stacks.
+pub fn label(price: &Price) -> String {
+    let key = price.key();
+    let mut label = key.name;
+    let parts = [
+        key.variant,
+        key.item_level.map(|level| format!("ilvl{}", level)),
+        key.links.map(|links| format!("{}L", links)),
+        key.gem_level
+            .map(|level| format!("{}/{}", level, key.gem_quality.unwrap_or(0))),
+        key.corrupted.then(|| String::from("corrupted")),
+    ];
+    for part in parts.into_iter().flatten() {
+        label.push(' ');
+        label.push_str(&part);
+    }
+    label
+}
+
+/// The key a stack of an item is held by, such as `Chaos Shard x10`.
+pub fn stack_key(key: &str, stack: u8) -> String {
+    format!("{} x{}", key, stack)
+}
+
+impl Assignment {
+    /// Reads an assignment saved by [Assignment::save], or an empty one if the file is missing.
+    pub fn load(path: &str) -> Result<Assignment, Error> {
+        match fs::read_to_string(path) {
+            Ok(text) => Ok(serde_json::from_str(&text)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Assignment::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Writes the assignment to a file.
+    pub fn save(&self, path: &str) -> Result<(), Error> {
+        fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+}